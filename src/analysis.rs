@@ -0,0 +1,435 @@
+/*!
+# analysis
+The `analysis` module contains methods to inspect a `StateSpace` system without stepping it, for
+example to check the poles of a controller before it is deployed.
+
+## Features.
+* `eigenvalues()` returns the poles of the system, the eigenvalues of A.
+* `is_stable()` checks the poles against the continuous or discrete stability region.
+* `check_euler_stability()` checks that `dt` is small enough for forward Euler to be stable.
+* `char_poly()` returns the coefficients of the characteristic polynomial `det(sI - A)`.
+* `dc_gain()` is the steady state gain from `u` to `y`, and `rga()` is its relative gain array.
+* `warm_start()` sets the state to the equilibrium for a constant input, the state behind
+  `dc_gain()`.
+* `is_proper()` and `is_strictly_proper()` check for direct feedthrough from `u` to `y`.
+* `controllability_matrix()` and `is_controllable()` check that every state can be reached.
+* `observability_matrix()` and `is_observable()` check that every state can be reconstructed
+  from the outputs.
+
+Rank decisions use the same tolerance as MATLAB's `rank()`, singular values smaller than
+`max(rows, cols) * max_singular_value * epsilon` are treated as zero, where `epsilon` is the
+machine precision of `T`.
+
+Only `char_poly()`, `dc_gain()`, `rga()`, `warm_start()`, `is_proper()`, and
+`is_strictly_proper()` are available without the `std` feature, the other methods use a
+dynamically sized copy of the matrices.
+*/
+
+#[cfg(feature = "std")]
+use na::{Complex, DMatrix};
+use na::{RealField, SMatrix, SVector};
+use nalgebra as na;
+use num_traits::NumCast;
+
+use crate::transform;
+use crate::{StateSpace, StateSpaceError, SystemTime};
+
+// The numerical rank of a matrix, see the module documentation for the tolerance.
+#[cfg(feature = "std")]
+fn rank<T: RealField>(m: &DMatrix<T>) -> usize {
+    let singular_values = m.clone().svd(false, false).singular_values;
+    let max_dim = T::from_usize(m.nrows().max(m.ncols())).expect("Conversion failed");
+    let max_singular_value = singular_values.max();
+    let tol = max_dim * max_singular_value * T::default_epsilon();
+    singular_values.iter().filter(|s| **s > tol).count()
+}
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: RealField + NumCast,
+{
+    /// The eigenvalues of A, which are the poles of the system. They are returned as complex
+    /// numbers since a real matrix can have complex conjugate eigenvalues.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; -2.0, -3.0]);
+    ///
+    /// let mut re: Vec<f64> = sys.eigenvalues().iter().map(|e| e.re).collect();
+    /// re.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    /// assert!((re[0] + 2.0).abs() < 1e-12);
+    /// assert!((re[1] + 1.0).abs() < 1e-12);
+    /// assert!(sys.eigenvalues().iter().all(|e| e.im == 0.0));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn eigenvalues(&self) -> SVector<Complex<T>, NX> {
+        // The Schur decomposition in nalgebra is not available for a generic const size, so the
+        // eigenvalues are computed on a dynamically sized copy of A.
+        let a = DMatrix::from_iterator(NX, NX, self.a.iter().cloned());
+        SVector::from_iterator(a.complex_eigenvalues().iter().cloned())
+    }
+
+    /// Checks that all poles are inside the stability region. The interpretation depends on
+    /// `get_system_time()`:
+    /// * Continuous: every eigenvalue of A has a negative real part.
+    /// * Discrete: every eigenvalue of A has a magnitude less than 1.
+    ///
+    /// Poles on the boundary, for example a pure integrator, are not stable.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    ///
+    /// // Poles at -1 and -2.
+    /// sys.set_a(matrix![0.0, 1.0; -2.0, -3.0]);
+    /// assert!(sys.is_stable());
+    ///
+    /// // Poles at +1 and -2.
+    /// sys.set_a(matrix![0.0, 1.0; 2.0, -1.0]);
+    /// assert!(!sys.is_stable());
+    ///
+    /// // The same A is stable or not depending on the declared system time. A pole at 0.5 is
+    /// // unstable in continuous time, and inside the unit circle in discrete time.
+    /// sys.set_a(matrix![0.5, 0.0; 0.0, -0.2]);
+    /// assert!(!sys.is_stable());
+    /// sys.set_discrete();
+    /// assert!(sys.is_stable());
+    ///
+    /// // A discrete pole at 1.5 is outside the unit circle, and -1.0 is on it.
+    /// sys.set_a(matrix![1.5, 0.0; 0.0, -0.2]);
+    /// assert!(!sys.is_stable());
+    /// sys.set_a(matrix![-1.0, 0.0; 0.0, -0.2]);
+    /// assert!(!sys.is_stable());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn is_stable(&self) -> bool {
+        let eigenvalues = self.eigenvalues();
+        match self.time {
+            SystemTime::Continuous => eigenvalues.iter().all(|e| e.re < T::zero()),
+            SystemTime::Discrete => eigenvalues
+                .iter()
+                .all(|e| e.re.clone() * e.re.clone() + e.im.clone() * e.im.clone() < T::one()),
+        }
+    }
+
+    /// Checks that forward Euler, the default integrator of `update()`, is stable at the current
+    /// `dt`: every eigenvalue of `I + A dt` has a magnitude less than 1. A stable continuous
+    /// system becomes unstable in simulation when `dt` is too large for its fastest pole, for
+    /// example a pole at `-a` needs `dt < 2 / a`. Call this after `set_dt()` to catch the
+    /// problem before the state diverges.
+    ///
+    /// A discrete system is stepped with A directly, so this is the same as `is_stable()`. The
+    /// check is for forward Euler, see `set_integrator()` and `update_rk4()` for alternatives
+    /// with a larger stability region.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::StateSpace;
+    ///
+    /// // A fast first order system with a pole at -50.
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![-50.0]).set_b(matrix![1.0]).set_c(matrix![1.0]);
+    /// assert!(sys.is_stable());
+    ///
+    /// // 1 - 50 * 0.05 = -1.5 is outside the unit circle.
+    /// sys.set_dt(0.05);
+    /// assert!(!sys.check_euler_stability());
+    ///
+    /// // 1 - 50 * 0.01 = 0.5 is inside.
+    /// sys.set_dt(0.01);
+    /// assert!(sys.check_euler_stability());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn check_euler_stability(&self) -> bool {
+        match self.time {
+            SystemTime::Continuous => {
+                let eye: SMatrix<T, NX, NX> = SMatrix::identity();
+                let m = eye + self.a.clone() * self.dt.clone();
+                let m = DMatrix::from_iterator(NX, NX, m.iter().cloned());
+                m.complex_eigenvalues()
+                    .iter()
+                    .all(|e| e.re.clone() * e.re.clone() + e.im.clone() * e.im.clone() < T::one())
+            }
+            SystemTime::Discrete => self.is_stable(),
+        }
+    }
+
+    /// The coefficients `[a1, ..., an]` of the characteristic polynomial of A,
+    /// `det(sI - A) = s^n + a1 s^(n-1) + ... + an`, whose roots are the poles. The polynomial is
+    /// monic, so the leading 1 is not returned: the `NX + 1` coefficients do not fit a const
+    /// generic size on stable Rust. The coefficients are found with the Faddeev-LeVerrier
+    /// recursion, on the stack and without finding the eigenvalues.
+    ///
+    /// For a 2x2 A the polynomial is `s^2 - trace(A) s + det(A)`.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, vector};
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![1.0, 2.0; 3.0, 4.0]);
+    /// let a = sys.get_a();
+    /// assert_eq!(vector![-a.trace(), a.determinant()], sys.char_poly());
+    /// assert_eq!(vector![-5.0, -2.0], sys.char_poly());
+    /// ```
+    pub fn char_poly(&self) -> SVector<T, NX> {
+        transform::char_poly(&self.a)
+    }
+
+    /// The DC (steady state) gain from `u` to `y`. The formula depends on `get_system_time()`:
+    /// * Continuous: `D - C * A^{-1} * B`
+    /// * Discrete: `D + C * (I - A)^{-1} * B`
+    ///
+    /// Returns `None` when the matrix to invert is singular, which means the system contains an
+    /// integrator and has no finite DC gain.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::StateSpace;
+    ///
+    /// // A first order lag, 3 / (s + 2).
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![-2.0]).set_b(matrix![3.0]).set_c(matrix![1.0]);
+    /// assert_eq!(Some(matrix![1.5]), sys.dc_gain());
+    ///
+    /// // An integrator, 1 / s.
+    /// sys.set_a(matrix![0.0]);
+    /// assert_eq!(None, sys.dc_gain());
+    /// ```
+    pub fn dc_gain(&self) -> Option<SMatrix<T, NY, NU>> {
+        match self.time {
+            SystemTime::Continuous => {
+                let a_inv = self.a.clone().try_inverse()?;
+                Some(self.d.clone() - self.c.clone() * a_inv * self.b.clone())
+            }
+            SystemTime::Discrete => {
+                let eye: SMatrix<T, NX, NX> = SMatrix::identity();
+                let m_inv = (eye - self.a.clone()).try_inverse()?;
+                Some(self.d.clone() + self.c.clone() * m_inv * self.b.clone())
+            }
+        }
+    }
+
+    /// The relative gain array `RGA = G .* (G^-1)^T` of the DC gain `G`, see `dc_gain()`, to
+    /// choose the input-output pairs of a decentralized MIMO controller. Element `(i, j)` is the
+    /// ratio of the gain from input `j` to output `i` with the other loops open to the same gain
+    /// with the other loops closed. Each row and column sums to 1, and pairs with an RGA near 1
+    /// are preferred. Negative elements mark pairs to avoid.
+    ///
+    /// Returns `None` if the system is not square, `NY != NU`, if there is no finite DC gain,
+    /// or if `G` is singular.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, SMatrix};
+    /// use state_space::StateSpace;
+    ///
+    /// // Two decoupled lags, so each output is paired with one input.
+    /// let mut sys: StateSpace<f64, 2, 2, 2> = StateSpace::new();
+    /// sys.set_a(matrix![-1.0, 0.0; 0.0, -2.0])
+    ///     .set_b(matrix![3.0, 0.0; 0.0, 1.0])
+    ///     .set_c(SMatrix::identity());
+    /// assert_eq!(Some(SMatrix::identity()), sys.rga());
+    ///
+    /// // With the DC gain [2, 1; 1, 2], the diagonal pairs have a relative gain of 4/3.
+    /// sys.set_a(-SMatrix::identity()).set_b(matrix![2.0, 1.0; 1.0, 2.0]);
+    /// let rga = sys.rga().unwrap();
+    /// assert!((rga - matrix![4.0, -1.0; -1.0, 4.0] / 3.0).norm() < 1e-12);
+    ///
+    /// // A system which is not square has no RGA.
+    /// let sys: StateSpace<f64, 1, 2, 2> = StateSpace::new();
+    /// assert_eq!(None, sys.rga());
+    /// ```
+    pub fn rga(&self) -> Option<SMatrix<T, NY, NU>> {
+        if NY != NU {
+            return None;
+        }
+        let g = self.dc_gain()?;
+        let square: SMatrix<T, NU, NU> = SMatrix::from_iterator(g.iter().cloned());
+        let inv_t = square.try_inverse()?.transpose();
+        let inv_t: SMatrix<T, NY, NU> = SMatrix::from_iterator(inv_t.iter().cloned());
+        Some(g.component_mul(&inv_t))
+    }
+
+    /// Sets `u` and the state to the equilibrium for the constant input `u`, so a system which
+    /// takes over part way through a run starts without a transient. The formula depends on
+    /// `get_system_time()`:
+    /// * Continuous: `x = -A^{-1} B u`, where `xDot = 0`.
+    /// * Discrete: `x = (I - A)^{-1} B u`, where `x(n+1) = x(n)`.
+    ///
+    /// The bounds of `u` and `x` are kept. The input applied before the next step is set to `u`
+    /// too, so a rate limit on `u` does not ramp the input away from the equilibrium. Returns
+    /// `StateSpaceError::SingularMatrix`, and leaves the system unchanged, if the matrix to
+    /// invert is singular, see `dc_gain()`.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, vector};
+    /// use state_space::{StateSpace, StateSpaceError};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; -2.0, -3.0])
+    ///     .set_b(matrix![0.0; 1.0])
+    ///     .set_c(matrix![1.0, 0.0])
+    ///     .set_dt(0.01);
+    ///
+    /// sys.warm_start(vector![4.0]).unwrap();
+    /// assert!((sys.get_x() - vector![2.0, 0.0]).norm() < 1e-12);
+    /// let x0 = sys.get_x();
+    /// sys.update();
+    /// assert!((sys.get_x() - x0).norm() < 1e-12);
+    /// assert!((sys.get_y()[0] - 2.0).abs() < 1e-12);
+    ///
+    /// // A rate limit on u does not move the state off the equilibrium.
+    /// sys.reset_all().get_u_mut().set_rate_limit(matrix![1.0]);
+    /// sys.warm_start(vector![4.0]).unwrap().update();
+    /// assert!((sys.get_x() - x0).norm() < 1e-12);
+    ///
+    /// // An integrator has no equilibrium for a non-zero input.
+    /// sys.set_a(matrix![0.0, 1.0; 0.0, -3.0]);
+    /// assert_eq!(Some(StateSpaceError::SingularMatrix), sys.warm_start(vector![4.0]).err());
+    /// ```
+    pub fn warm_start(&mut self, u: SVector<T, NU>) -> Result<&mut Self, StateSpaceError> {
+        let x = match self.time {
+            SystemTime::Continuous => {
+                let a_inv = self
+                    .a
+                    .clone()
+                    .try_inverse()
+                    .ok_or(StateSpaceError::SingularMatrix)?;
+                -(a_inv * &self.b * &u)
+            }
+            SystemTime::Discrete => {
+                let eye: SMatrix<T, NX, NX> = SMatrix::identity();
+                let m_inv = (eye - self.a.clone())
+                    .try_inverse()
+                    .ok_or(StateSpaceError::SingularMatrix)?;
+                m_inv * &self.b * &u
+            }
+        };
+        self.u_applied = u.clone();
+        self.u.set_val(u);
+        self.x.set_val(x);
+        Ok(self)
+    }
+
+    /// The controllability matrix `[B, AB, A^2 B, ..., A^(NX-1) B]`, which has NX rows and
+    /// `NX * NU` columns. The product of two const generics can not be used as a matrix size, so
+    /// a dynamically sized matrix is returned.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, DMatrix};
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; -2.0, -3.0]).set_b(matrix![0.0; 1.0]);
+    ///
+    /// let exp = DMatrix::from_row_slice(2, 2, &[0.0, 1.0, 1.0, -3.0]);
+    /// assert_eq!(exp, sys.controllability_matrix());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn controllability_matrix(&self) -> DMatrix<T> {
+        let mut ctrb = DMatrix::zeros(NX, NX * NU);
+        let mut a_k_b = self.b.clone();
+        for k in 0..NX {
+            ctrb.fixed_view_mut::<NX, NU>(0, k * NU).copy_from(&a_k_b);
+            a_k_b = self.a.clone() * a_k_b;
+        }
+        ctrb
+    }
+
+    /// Checks that the controllability matrix has full row rank, NX. See the module
+    /// documentation for the tolerance of the rank decision.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; -2.0, -3.0]).set_b(matrix![0.0; 1.0]);
+    /// assert!(sys.is_controllable());
+    ///
+    /// // The input does not reach the second state.
+    /// sys.set_a(matrix![-1.0, 0.0; 0.0, -2.0]).set_b(matrix![1.0; 0.0]);
+    /// assert!(!sys.is_controllable());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn is_controllable(&self) -> bool {
+        rank(&self.controllability_matrix()) == NX
+    }
+
+    /// The observability matrix `[C; CA; CA^2; ...; CA^(NX-1)]`, which has `NX * NY` rows and NX
+    /// columns. As for `controllability_matrix()`, a dynamically sized matrix is returned.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, DMatrix};
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; -2.0, -3.0]).set_c(matrix![1.0, 0.0]);
+    ///
+    /// let exp = DMatrix::from_row_slice(2, 2, &[1.0, 0.0, 0.0, 1.0]);
+    /// assert_eq!(exp, sys.observability_matrix());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn observability_matrix(&self) -> DMatrix<T> {
+        let mut obsv = DMatrix::zeros(NX * NY, NX);
+        let mut c_a_k = self.c.clone();
+        for k in 0..NX {
+            obsv.fixed_view_mut::<NY, NX>(k * NY, 0).copy_from(&c_a_k);
+            c_a_k *= self.a.clone();
+        }
+        obsv
+    }
+
+    /// Checks that the observability matrix has full column rank, NX. See the module
+    /// documentation for the tolerance of the rank decision.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; -2.0, -3.0]).set_c(matrix![1.0, 0.0]);
+    /// assert!(sys.is_observable());
+    ///
+    /// // The second state never shows up in the output.
+    /// sys.set_a(matrix![-1.0, 0.0; 0.0, -2.0]).set_c(matrix![1.0, 0.0]);
+    /// assert!(!sys.is_observable());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn is_observable(&self) -> bool {
+        rank(&self.observability_matrix()) == NX
+    }
+
+    /// Always true. A state space system `C (sI - A)^-1 B + D` is proper by construction, its
+    /// transfer functions never have a numerator of higher degree than the denominator. See
+    /// `is_strictly_proper()` for the check which depends on D.
+    pub fn is_proper(&self) -> bool {
+        true
+    }
+
+    /// True if D is all zeros, so there is no direct feedthrough from `u` to `y` and the gain
+    /// falls to zero at high frequency. A strictly proper system in a feedback loop can not form
+    /// an algebraic loop, see `feedback()`.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![-1.0]).set_b(matrix![1.0]).set_c(matrix![1.0]);
+    /// assert!(sys.is_proper());
+    /// assert!(sys.is_strictly_proper());
+    ///
+    /// sys.set_d(matrix![0.5]);
+    /// assert!(sys.is_proper());
+    /// assert!(!sys.is_strictly_proper());
+    /// ```
+    pub fn is_strictly_proper(&self) -> bool {
+        self.d.iter().all(|v| *v == T::zero())
+    }
+}
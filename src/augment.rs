@@ -0,0 +1,309 @@
+/*!
+# augment
+The `augment` module adds states to a `StateSpace` system to prepare it for controller design.
+
+## Features.
+* `augment_integrator()` adds one integral-of-error state per output, for integral action in a
+  servo design such as LQR.
+* `with_input_delay()` adds a Pade approximation of a transport delay to one input.
+* `clone_resized()` embeds a system in a larger one, for manual composition of block systems.
+*/
+
+use na::{RealField, SMatrix};
+use nalgebra as na;
+use num_traits::{NumCast, One, Zero};
+
+use crate::{StateSpace, StateSpaceError, SysVec};
+
+// Copies v into the first N elements of a SysVec of M elements, the others are zero with the
+// default bounds. If v has a rate limit, the others get a rate limit of 9e99, in effect none.
+fn embed<T, const N: usize, const M: usize>(v: &SysVec<T, N>) -> SysVec<T, M>
+where
+    T: nalgebra::Scalar + PartialOrd + NumCast + Zero,
+{
+    let mut out: SysVec<T, M> = SysVec::new();
+    let (mut val, mut lb, mut ub) = (out.get_val(), out.get_lb(), out.get_ub());
+    val.fixed_rows_mut::<N>(0).copy_from(v.val_ref());
+    lb.fixed_rows_mut::<N>(0).copy_from(&v.get_lb());
+    ub.fixed_rows_mut::<N>(0).copy_from(&v.get_ub());
+    if let Some(rate) = v.get_rate_limit() {
+        let mut rate_m = ub.clone();
+        rate_m.fixed_rows_mut::<N>(0).copy_from(&rate);
+        out.set_rate_limit(rate_m);
+    }
+    out.set_val(val).set_lb(lb).set_ub(ub);
+    out
+}
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    /// Augments the plant with the integral of the tracking error `x_i' = r - y` for each output,
+    /// with the reference `r = 0`. The augmented state is `[x; x_i]` with `NXA = NX + NY` states,
+    /// and the matrices are:
+    /// ```text
+    /// A_aug = [  A  0 ]    B_aug = [  B ]    C_aug = [ C  0 ]    D_aug = D
+    ///         [ -C  0 ]            [ -D ]
+    /// ```
+    /// A state feedback `u = -K [x; x_i]` designed for the augmented system has integral action,
+    /// and a non-zero reference is tracked by adding `r` to `x_i'` in the controller. The
+    /// outputs, `dt`, the system time, and the bounds of `u` and `y` are unchanged, and the
+    /// state starts at zero. A discrete system gets summing states, `x_i(n+1) = x_i - y`.
+    ///
+    /// Returns `StateSpaceError::DimensionMismatch` if `NXA != NX + NY`.
+    ///
+    /// This example holds the output of a static gain at 2.0, so the integral state falls at a
+    /// rate of 2.0 per second.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut plant: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// plant.set_a(matrix![-1.0])
+    ///     .set_b(matrix![1.0])
+    ///     .set_d(matrix![2.0])
+    ///     .set_dt(0.1);
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = plant.augment_integrator().unwrap();
+    /// assert_eq!(matrix![-1.0, 0.0; 0.0, 0.0], sys.get_a());
+    /// assert_eq!(matrix![1.0; -2.0], sys.get_b());
+    ///
+    /// sys.set_u(SysVec::from_val(1.0));
+    /// for _ in 0..10 {
+    ///     sys.update();
+    /// }
+    /// assert!((sys.get_x()[1] + 2.0).abs() < 1e-12);
+    /// ```
+    pub fn augment_integrator<const NXA: usize>(
+        &self,
+    ) -> Result<StateSpace<T, NU, NXA, NY>, StateSpaceError>
+    where
+        T: nalgebra::ClosedSubAssign,
+    {
+        if NXA != NX + NY {
+            return Err(StateSpaceError::DimensionMismatch);
+        }
+
+        let mut a: SMatrix<T, NXA, NXA> = SMatrix::zeros();
+        a.fixed_view_mut::<NX, NX>(0, 0).copy_from(&self.a);
+        a.fixed_view_mut::<NY, NX>(NX, 0)
+            .copy_from(&(SMatrix::zeros() - self.c.clone()));
+
+        let mut b: SMatrix<T, NXA, NU> = SMatrix::zeros();
+        b.fixed_view_mut::<NX, NU>(0, 0).copy_from(&self.b);
+        b.fixed_view_mut::<NY, NU>(NX, 0)
+            .copy_from(&(SMatrix::zeros() - self.d.clone()));
+
+        let mut c: SMatrix<T, NY, NXA> = SMatrix::zeros();
+        c.fixed_view_mut::<NY, NX>(0, 0).copy_from(&self.c);
+
+        let mut sys = StateSpace::new();
+        sys.set_a(a)
+            .set_b(b)
+            .set_c(c)
+            .set_d(self.d.clone())
+            .set_u(self.u.clone())
+            .set_y(self.y.clone())
+            .set_dt(self.dt.clone());
+        sys.time = self.time;
+        Ok(sys)
+    }
+
+    /// Embeds the system in the top left blocks of a larger system with `NU2` inputs, `NX2`
+    /// states, and `NY2` outputs. The extra rows and columns of the matrices are zero, so the
+    /// extra states stay at zero and the extra outputs are zero, until they are connected by
+    /// changing the matrices of the larger system. `u`, `x`, and `y` are copied with their
+    /// bounds, the extra elements are zero with the default bounds. `dt` and the system time are
+    /// unchanged, the names are dropped.
+    ///
+    /// Returns `StateSpaceError::DimensionMismatch` if a target size is smaller than the system.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, SVector};
+    /// use state_space::{StateSpace, StateSpaceError, SysVec};
+    ///
+    /// let mut small: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// small.set_a(matrix![-1.0])
+    ///     .set_b(matrix![1.0])
+    ///     .set_c(matrix![2.0])
+    ///     .set_u(SysVec::from_val(1.0))
+    ///     .set_dt(0.1);
+    ///
+    /// let mut large: StateSpace<f64, 1, 3, 1> = small.clone_resized().unwrap();
+    /// assert_eq!(matrix![-1.0, 0.0, 0.0; 0.0, 0.0, 0.0; 0.0, 0.0, 0.0], large.get_a());
+    /// for _ in 0..20 {
+    ///     small.update();
+    ///     large.update();
+    ///     assert_eq!(small.get_x()[0], large.get_x()[0]);
+    ///     assert_eq!(SVector::<f64, 2>::zeros(), large.get_x().fixed_rows::<2>(1));
+    ///     assert_eq!(small.get_y(), large.get_y());
+    /// }
+    ///
+    /// let err = large.clone_resized::<1, 2, 1>().err();
+    /// assert_eq!(Some(StateSpaceError::DimensionMismatch), err);
+    /// ```
+    pub fn clone_resized<const NU2: usize, const NX2: usize, const NY2: usize>(
+        &self,
+    ) -> Result<StateSpace<T, NU2, NX2, NY2>, StateSpaceError> {
+        if NU2 < NU || NX2 < NX || NY2 < NY {
+            return Err(StateSpaceError::DimensionMismatch);
+        }
+
+        let mut a: SMatrix<T, NX2, NX2> = SMatrix::zeros();
+        a.fixed_view_mut::<NX, NX>(0, 0).copy_from(&self.a);
+        let mut b: SMatrix<T, NX2, NU2> = SMatrix::zeros();
+        b.fixed_view_mut::<NX, NU>(0, 0).copy_from(&self.b);
+        let mut c: SMatrix<T, NY2, NX2> = SMatrix::zeros();
+        c.fixed_view_mut::<NY, NX>(0, 0).copy_from(&self.c);
+        let mut d: SMatrix<T, NY2, NU2> = SMatrix::zeros();
+        d.fixed_view_mut::<NY, NU>(0, 0).copy_from(&self.d);
+
+        let mut sys = StateSpace::new();
+        sys.set_a(a)
+            .set_b(b)
+            .set_c(c)
+            .set_d(d)
+            .set_u(embed(&self.u))
+            .set_x(embed(&self.x))
+            .set_y(embed(&self.y))
+            .set_dt(self.dt.clone());
+        sys.time = self.time;
+        Ok(sys)
+    }
+}
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: RealField + NumCast,
+{
+    /// Adds a transport delay of `tau` to input `channel`, with an `order` Pade approximation of
+    /// `exp(-s tau)`. The approximation is an all-pass filter, so only the phase of the channel
+    /// changes, and it matches the phase `-w tau` of the delay well up to about
+    /// `w tau = order`. The augmented state is `[x; x_d]`, where the `order` states of the delay
+    /// are in controllable canonical form, so `NXA = NX + order`. With the delay
+    /// `x_d' = A_d x_d + B_d u_j`, `u_j(delayed) = C_d x_d + D_d u_j`:
+    /// ```text
+    /// A_aug = [ A  B_j C_d ]    B_aug = [ B diag(1, .., D_d, .., 1) ]
+    ///         [ 0    A_d   ]            [ B_d e_j^T                 ]
+    /// C_aug = [ C  D_j C_d ]    D_aug = D diag(1, .., D_d, .., 1)
+    /// ```
+    /// `B_j` and `D_j` are column `j` of B and D. The outputs, `dt`, the system time, and the
+    /// bounds of `u` and `y` are unchanged, and the state starts at zero. A delay is continuous,
+    /// so the system should be continuous too.
+    ///
+    /// Returns an error if:
+    /// * `channel` is not an input, `StateSpaceError::IndexOutOfRange`.
+    /// * `tau` is not positive, `StateSpaceError::InvalidPolynomial`, since the Pade polynomials
+    ///   are then degenerate.
+    /// * `NXA != NX + order`, `StateSpaceError::DimensionMismatch`.
+    ///
+    /// This example delays the input of `1 / (s + 1)` by 0.5, and compares the phase at 1 rad/s
+    /// with the exact `-atan(w) - w tau`.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::StateSpace;
+    ///
+    /// let mut plant: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// plant.set_a(matrix![-1.0]).set_b(matrix![1.0]).set_c(matrix![1.0]);
+    ///
+    /// let (w, tau) = (1.0_f64, 0.5);
+    /// let sys: StateSpace<f64, 1, 4, 1> = plant.with_input_delay(0, tau, 3).unwrap();
+    /// let g = sys.freq_response(&[w]).unwrap()[0];
+    /// let g_plant = plant.freq_response(&[w]).unwrap()[0];
+    /// assert!((g.arg() - (-w.atan() - w * tau)).abs() < 1e-6);
+    /// assert!((g.norm() - g_plant.norm()).abs() < 1e-12);
+    ///
+    /// assert!(plant.with_input_delay::<4>(1, tau, 3).is_err());
+    /// assert!(plant.with_input_delay::<3>(0, tau, 3).is_err());
+    /// ```
+    pub fn with_input_delay<const NXA: usize>(
+        &self,
+        channel: usize,
+        tau: T,
+        order: usize,
+    ) -> Result<StateSpace<T, NU, NXA, NY>, StateSpaceError> {
+        if channel >= NU {
+            return Err(StateSpaceError::IndexOutOfRange);
+        }
+        if tau <= T::zero() {
+            return Err(StateSpaceError::InvalidPolynomial);
+        }
+        if NXA != NX + order {
+            return Err(StateSpaceError::DimensionMismatch);
+        }
+
+        // Pade denominator coefficients c_k tau^k of s^k, c_k = (2n-k)! n! / ((2n)! k! (n-k)!).
+        // Stored at NX + i - 1 for i = n - k, which is a_i in descending powers of s.
+        let n = order;
+        let mut coeffs: SMatrix<T, NXA, 1> = SMatrix::zeros();
+        let mut term = T::one();
+        for k in 0..n {
+            coeffs[NX + n - k - 1] = term.clone();
+            term = term * tau.clone() * T::from(n - k).expect("Conversion failed")
+                / T::from((2 * n - k) * (k + 1)).expect("Conversion failed");
+        }
+        let lead = term;
+
+        // Controllable canonical form of the delay, see from_tf(). D_d = (-1)^n, and
+        // b_i = (-1)^(n-i) a_i, so C_d = b_i - D_d a_i is zero for even i.
+        let d_d = if n.is_multiple_of(2) {
+            T::one()
+        } else {
+            -T::one()
+        };
+        let mut a: SMatrix<T, NXA, NXA> = SMatrix::zeros();
+        a.fixed_view_mut::<NX, NX>(0, 0).copy_from(&self.a);
+        let mut c: SMatrix<T, NY, NXA> = SMatrix::zeros();
+        c.fixed_view_mut::<NY, NX>(0, 0).copy_from(&self.c);
+        let mut c_d: SMatrix<T, 1, NXA> = SMatrix::zeros();
+        for i in 1..=n {
+            let a_i = coeffs[NX + i - 1].clone() / lead.clone();
+            let b_i = if (n - i).is_multiple_of(2) {
+                a_i.clone()
+            } else {
+                -a_i.clone()
+            };
+            a[(NX, NX + i - 1)] = -a_i.clone();
+            if i > 1 {
+                a[(NX + i - 1, NX + i - 2)] = T::one();
+            }
+            c_d[NX + i - 1] = b_i - d_d.clone() * a_i;
+        }
+
+        // The delayed input drives the plant through column j of B and D.
+        let b_j = self.b.column(channel).into_owned();
+        let d_j = self.d.column(channel).into_owned();
+        let mut top = a.fixed_view_mut::<NX, NXA>(0, 0);
+        top += &b_j * &c_d;
+        c += &d_j * &c_d;
+
+        let mut b: SMatrix<T, NXA, NU> = SMatrix::zeros();
+        b.fixed_view_mut::<NX, NU>(0, 0).copy_from(&self.b);
+        let mut d = self.d.clone();
+        b.fixed_view_mut::<NX, 1>(0, channel).scale_mut(d_d.clone());
+        d.column_mut(channel).scale_mut(d_d);
+        if n > 0 {
+            b[(NX, channel)] = T::one();
+        }
+
+        let mut sys = StateSpace::new();
+        sys.set_a(a)
+            .set_b(b)
+            .set_c(c)
+            .set_d(d)
+            .set_u(self.u.clone())
+            .set_y(self.y.clone())
+            .set_dt(self.dt.clone());
+        sys.time = self.time;
+        Ok(sys)
+    }
+}
@@ -0,0 +1,137 @@
+/*!
+# builder
+The `builder` module contains `StateSpaceBuilder`, which creates a `StateSpace` only after
+checking that the system is complete. With the chained setters of `StateSpace` a forgotten
+matrix silently stays at zero, the builder reports it instead.
+
+## Features.
+* `A`, `B`, `C`, and `dt` must be set, `D` defaults to zero.
+* `dt` must be positive.
+*/
+
+use na::SMatrix;
+use nalgebra as na;
+use num_traits::{NumCast, One, Zero};
+
+use crate::{StateSpace, StateSpaceError, SystemTime};
+
+/// Builder for a `StateSpace`, which tracks which fields were explicitly set.
+///
+/// ```rust
+/// use nalgebra::matrix;
+/// use state_space::{StateSpaceBuilder, StateSpaceError};
+///
+/// let sys = StateSpaceBuilder::<f64, 1, 1, 1>::new()
+///     .set_a(matrix![-1.0])
+///     .set_b(matrix![1.0])
+///     .set_c(matrix![1.0])
+///     .set_dt(0.1)
+///     .build()
+///     .unwrap();
+/// assert_eq!(0.1, sys.get_dt());
+///
+/// // Without set_dt() the build fails, even though StateSpace::new() has a default dt of 1.0.
+/// let result = StateSpaceBuilder::<f64, 1, 1, 1>::new()
+///     .set_a(matrix![-1.0])
+///     .set_b(matrix![1.0])
+///     .set_c(matrix![1.0])
+///     .build();
+/// assert_eq!(Some(StateSpaceError::MissingField("dt")), result.err());
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct StateSpaceBuilder<T, const NU: usize, const NX: usize, const NY: usize> {
+    a: Option<SMatrix<T, NX, NX>>,
+    b: Option<SMatrix<T, NX, NU>>,
+    c: Option<SMatrix<T, NY, NX>>,
+    d: Option<SMatrix<T, NY, NU>>,
+    dt: Option<T>,
+    time: SystemTime,
+}
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpaceBuilder<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    /// Create a new builder with nothing set.
+    pub fn new() -> Self {
+        Self {
+            a: None,
+            b: None,
+            c: None,
+            d: None,
+            dt: None,
+            time: SystemTime::Continuous,
+        }
+    }
+
+    // Setters mirror the setters of StateSpace, and can be chained in the same way.
+    pub fn set_a(&mut self, mat: SMatrix<T, NX, NX>) -> &mut Self {
+        self.a = Some(mat);
+        self
+    }
+
+    pub fn set_b(&mut self, mat: SMatrix<T, NX, NU>) -> &mut Self {
+        self.b = Some(mat);
+        self
+    }
+
+    pub fn set_c(&mut self, mat: SMatrix<T, NY, NX>) -> &mut Self {
+        self.c = Some(mat);
+        self
+    }
+
+    pub fn set_d(&mut self, mat: SMatrix<T, NY, NU>) -> &mut Self {
+        self.d = Some(mat);
+        self
+    }
+
+    pub fn set_dt(&mut self, dt: T) -> &mut Self {
+        self.dt = Some(dt);
+        self
+    }
+
+    pub fn set_discrete(&mut self) -> &mut Self {
+        self.time = SystemTime::Discrete;
+        self
+    }
+
+    /// Creates the `StateSpace`. The system vectors have their default values. Errors:
+    /// * `MissingField(name)` if A, B, C, or dt was never set.
+    /// * `NonPositiveDt` if dt is zero or negative.
+    pub fn build(&self) -> Result<StateSpace<T, NU, NX, NY>, StateSpaceError> {
+        let a = self.a.clone().ok_or(StateSpaceError::MissingField("a"))?;
+        let b = self.b.clone().ok_or(StateSpaceError::MissingField("b"))?;
+        let c = self.c.clone().ok_or(StateSpaceError::MissingField("c"))?;
+        let dt = self.dt.clone().ok_or(StateSpaceError::MissingField("dt"))?;
+
+        let mut sys = StateSpace::new();
+        sys.try_set_dt(dt)?.set_a(a).set_b(b).set_c(c);
+        if let Some(d) = self.d.clone() {
+            sys.set_d(d);
+        }
+        sys.time = self.time;
+        Ok(sys)
+    }
+}
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> Default
+    for StateSpaceBuilder<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
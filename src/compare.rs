@@ -0,0 +1,81 @@
+/*!
+# compare
+The `compare` module compares two `StateSpace` systems within a tolerance, to test a generated or
+loaded system against a reference.
+
+## Features.
+* `approx_eq()` compares the matrices, the bounds, and `dt` element by element.
+
+A derived `PartialEq` would compare floating point values exactly, which fails for systems that
+differ only by round off, so `StateSpace` does not implement it.
+*/
+
+use na::{RealField, SMatrix};
+use nalgebra as na;
+use num_traits::NumCast;
+
+use crate::{StateSpace, SysVec};
+
+// Checks that every element of a and b differs by at most tol.
+fn matrix_eq<T: RealField, const R: usize, const C: usize>(
+    a: &SMatrix<T, R, C>,
+    b: &SMatrix<T, R, C>,
+    tol: &T,
+) -> bool {
+    a.iter()
+        .zip(b.iter())
+        .all(|(a, b)| (a.clone() - b.clone()).abs() <= *tol)
+}
+
+// Checks the bounds of a and b, but not their values.
+fn bounds_eq<T: RealField + NumCast, const N: usize>(
+    a: &SysVec<T, N>,
+    b: &SysVec<T, N>,
+    tol: &T,
+) -> bool {
+    matrix_eq(&a.get_lb(), &b.get_lb(), tol) && matrix_eq(&a.get_ub(), &b.get_ub(), tol)
+}
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: RealField + NumCast,
+{
+    /// Checks that `self` and `other` are the same system within `tol`: every element of `A`,
+    /// `B`, `C`, and `D`, the bounds of `u`, `x`, and `y`, and `dt` differ by at most `tol`, and
+    /// both are continuous or both are discrete. The values of `u`, `x`, and `y` are the state of
+    /// a simulation rather than part of the system, and are not compared.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; -2.0, -3.0])
+    ///     .set_b(matrix![0.0; 1.0])
+    ///     .set_c(matrix![1.0, 0.0])
+    ///     .set_dt(0.01);
+    ///
+    /// let mut close = sys;
+    /// close.set_a(matrix![0.0, 1.0; -2.0 + 1e-12, -3.0]);
+    /// assert!(sys.approx_eq(&close, 1e-9));
+    ///
+    /// let mut far = sys;
+    /// far.set_a(matrix![0.0, 1.0; -2.0 + 1e-3, -3.0]);
+    /// assert!(!sys.approx_eq(&far, 1e-9));
+    ///
+    /// far = sys;
+    /// far.set_dt(0.01 + 1e-3);
+    /// assert!(!sys.approx_eq(&far, 1e-9));
+    /// ```
+    pub fn approx_eq(&self, other: &Self, tol: T) -> bool {
+        self.time == other.time
+            && (self.dt.clone() - other.dt.clone()).abs() <= tol
+            && matrix_eq(&self.a, &other.a, &tol)
+            && matrix_eq(&self.b, &other.b, &tol)
+            && matrix_eq(&self.c, &other.c, &tol)
+            && matrix_eq(&self.d, &other.d, &tol)
+            && bounds_eq(&self.u, &other.u, &tol)
+            && bounds_eq(&self.x, &other.x, &tol)
+            && bounds_eq(&self.y, &other.y, &tol)
+    }
+}
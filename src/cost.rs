@@ -0,0 +1,129 @@
+/*!
+# cost
+The `cost` module evaluates the quadratic cost `x^T Q x + u^T R u` of an LQR design, to monitor a
+controller and to compare trajectories.
+
+## Features.
+* `quadratic_cost()` evaluates the cost of the current `x` and `u` of a `StateSpace` system.
+* `QuadraticCost` accumulates the cost over the steps of a run.
+*/
+
+use na::SMatrix;
+use nalgebra as na;
+use num_traits::{NumCast, One, Zero};
+
+use crate::{StateSpace, SystemTime};
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    /// The quadratic cost `x^T Q x + u^T R u` of the current state and input. After `update()`
+    /// the state is `x(n+1)` while the input is still `u(n)`, see `update()`.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, vector};
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let q = matrix![2.0, 0.0; 0.0, 1.0];
+    /// let r = matrix![0.5];
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// assert_eq!(0.0, sys.quadratic_cost(&q, &r));
+    ///
+    /// let mut x: SysVec<f64, 2> = SysVec::new();
+    /// x.set_val(vector![1.0, -3.0]);
+    /// sys.set_x(x).set_u(SysVec::from_val(2.0));
+    /// // 2 * 1^2 + 1 * (-3)^2 + 0.5 * 2^2
+    /// assert_eq!(13.0, sys.quadratic_cost(&q, &r));
+    /// ```
+    pub fn quadratic_cost(&self, q: &SMatrix<T, NX, NX>, r: &SMatrix<T, NU, NU>) -> T {
+        let x = self.x.val_ref();
+        let u = self.u.val_ref();
+        x.dot(&(q * x)) + u.dot(&(r * u))
+    }
+}
+
+/// Running total of the quadratic cost of a `StateSpace` system, the LQR cost
+/// `J = integral(x^T Q x + u^T R u) dt` of a continuous system or `J = sum(x^T Q x + u^T R u)` of
+/// a discrete system. Each call to `accumulate()` adds the current cost, times `dt` for a
+/// continuous system, a rectangle rule approximation of the integral.
+///
+/// This example compares two feedback gains on the plant `xDot = u`, with `u = -k x`. The exact
+/// cost from `x(0) = 1` with `Q = R = 1` is `(1 + k^2) / (2 k)`, smallest at the LQR gain `k = 1`.
+///
+/// ```rust
+/// use nalgebra::matrix;
+/// use state_space::{QuadraticCost, StateSpace, SysVec};
+///
+/// let run = |k: f64| {
+///     let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+///     sys.set_b(matrix![1.0])
+///         .set_x(SysVec::from_val(1.0))
+///         .set_dt(0.001);
+///     let mut cost = QuadraticCost::new(matrix![1.0], matrix![1.0]);
+///     for _ in 0..20000 {
+///         let u = -k * sys.get_x()[0];
+///         sys.set_u(SysVec::from_val(u));
+///         cost.accumulate(&sys);
+///         sys.update();
+///     }
+///     cost.get_total()
+/// };
+///
+/// let (lqr, aggressive) = (run(1.0), run(4.0));
+/// assert!((lqr - 1.0).abs() < 1e-3);
+/// assert!((aggressive - 17.0 / 8.0).abs() < 1e-2);
+/// assert!(lqr < aggressive);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct QuadraticCost<T, const NU: usize, const NX: usize> {
+    q: SMatrix<T, NX, NX>,
+    r: SMatrix<T, NU, NU>,
+    total: T,
+}
+
+impl<T, const NU: usize, const NX: usize> QuadraticCost<T, NU, NX>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    /// Create an accumulator with the weights `Q` and `R`, and a total of zero.
+    pub fn new(q: SMatrix<T, NX, NX>, r: SMatrix<T, NU, NU>) -> Self {
+        Self {
+            q,
+            r,
+            total: T::zero(),
+        }
+    }
+
+    /// Adds the cost of the current `x` and `u` of `sys` to the total, see `quadratic_cost()`.
+    pub fn accumulate<const NY: usize>(&mut self, sys: &StateSpace<T, NU, NX, NY>) -> &mut Self {
+        let cost = sys.quadratic_cost(&self.q, &self.r);
+        self.total += match sys.get_system_time() {
+            SystemTime::Continuous => cost * sys.dt.clone(),
+            SystemTime::Discrete => cost,
+        };
+        self
+    }
+
+    pub fn get_total(&self) -> T {
+        self.total.clone()
+    }
+
+    /// Sets the total back to zero, the weights are kept.
+    pub fn reset(&mut self) -> &mut Self {
+        self.total = T::zero();
+        self
+    }
+}
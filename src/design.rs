@@ -0,0 +1,303 @@
+/*!
+# design
+The `design` module computes state feedback gains for a `StateSpace` plant, to close the loop
+with `u = -K x` directly from the model rather than in a separate design tool.
+
+## Features.
+* `place_poles()` places the closed loop poles of a single input system with Ackermann's formula.
+* `lqr()` computes the optimal gain of a continuous system from the algebraic Riccati equation.
+  The Riccati equation is solved with dynamically sized matrices, so `lqr()` requires the `std`
+  feature.
+* `compensator()` combines a state feedback gain and an observer gain into one dynamic
+  compensator, the LQG controller form.
+*/
+
+#[cfg(feature = "std")]
+use na::DMatrix;
+use na::{Complex, RealField, SMatrix, SVector};
+use nalgebra as na;
+use num_traits::NumCast;
+
+use crate::transform::real_poly_from_roots;
+#[cfg(feature = "std")]
+use crate::validate::symmetry_tol;
+#[cfg(feature = "std")]
+use crate::SystemTime;
+#[cfg(feature = "std")]
+use crate::{is_positive_definite, is_symmetric};
+use crate::{StateSpace, StateSpaceError};
+
+// Iteration limit and relative tolerance of the matrix sign function used by lqr().
+#[cfg(feature = "std")]
+const SIGN_MAX_ITER: usize = 100;
+#[cfg(feature = "std")]
+const SIGN_REL_TOL: f64 = 1e-12;
+
+// The matrix sign function of z, with the scaled Newton iteration z = (c z + (c z)^-1) / 2 and
+// c = |det z|^(-1/n). Returns None if an iterate is singular or the iteration does not converge,
+// which happens when z has eigenvalues on the imaginary axis.
+#[cfg(feature = "std")]
+fn matrix_sign<T: RealField + NumCast>(mut z: DMatrix<T>) -> Option<DMatrix<T>> {
+    let n = T::from(z.nrows()).expect("Conversion failed");
+    let half = T::from(0.5).expect("Conversion failed");
+    let tol = T::from(SIGN_REL_TOL).expect("Conversion failed");
+    for _ in 0..SIGN_MAX_ITER {
+        let det = z.determinant().abs();
+        if det == T::zero() {
+            return None;
+        }
+        let c = det.powf(-T::one() / n.clone());
+        let z_inv = z.clone().try_inverse()?;
+        let next = (&z * c.clone() + z_inv / c) * half.clone();
+        let step = (&next - &z).norm();
+        z = next;
+        if step <= tol.clone() * z.norm() {
+            return Some(z);
+        }
+    }
+    None
+}
+
+impl<T, const NX: usize, const NY: usize> StateSpace<T, 1, NX, NY>
+where
+    T: RealField + NumCast,
+{
+    /// State feedback gain `K` which places the eigenvalues of `A - B K` at `desired`, with
+    /// Ackermann's formula `K = [0 ... 0 1] W^-1 phi(A)`. `W` is the controllability matrix and
+    /// `phi(s) = s^n + alpha1 s^(n-1) + ... + alphan` has the desired poles as its roots. Complex
+    /// poles must be given with their conjugates, so that `K` is real. For a discrete system the
+    /// poles are in the z plane.
+    ///
+    /// Ackermann's formula is exact, but becomes poorly conditioned for more than a handful of
+    /// states or for poles far from the open loop poles.
+    ///
+    /// Returns an error if:
+    /// * There are not `NX` poles, `StateSpaceError::DimensionMismatch`.
+    /// * A complex pole has no conjugate, `StateSpaceError::InvalidPolynomial`.
+    /// * The system is not controllable, `StateSpaceError::NotControllable`, with the rank test
+    ///   of `is_controllable()`. Without the `std` feature only an exactly singular
+    ///   controllability matrix is detected.
+    ///
+    /// This example places the poles of a double integrator at `-1 +/- 1j`, so that
+    /// `phi(s) = s^2 + 2 s + 2` and `K = [2 2]`.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, Complex};
+    /// use state_space::{StateSpace, StateSpaceError};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; 0.0, 0.0])
+    ///     .set_b(matrix![0.0; 1.0])
+    ///     .set_c(matrix![1.0, 0.0]);
+    ///
+    /// let poles = [Complex::new(-1.0, 1.0), Complex::new(-1.0, -1.0)];
+    /// let k = sys.place_poles(&poles).unwrap();
+    /// assert!((k - matrix![2.0, 2.0]).norm() < 1e-12);
+    ///
+    /// let mut closed_loop = sys;
+    /// closed_loop.set_a(sys.get_a() - sys.get_b() * k);
+    /// for p in closed_loop.eigenvalues().iter() {
+    ///     assert!(poles.iter().any(|d| (d - p).norm() < 1e-9));
+    /// }
+    ///
+    /// let err = sys.place_poles(&poles[..1]).err();
+    /// assert_eq!(Some(StateSpaceError::DimensionMismatch), err);
+    /// let err = sys.place_poles(&[Complex::new(-1.0, 1.0), Complex::new(-2.0, 0.0)]).err();
+    /// assert_eq!(Some(StateSpaceError::InvalidPolynomial), err);
+    ///
+    /// // Two modes which are equal to rounding error can not both be placed.
+    /// sys.set_a(matrix![-1.0, 0.0; 0.0, -1.0 - 1e-15]).set_b(matrix![1.0; 1.0]);
+    /// let err = sys.place_poles(&poles).err();
+    /// assert_eq!(Some(StateSpaceError::NotControllable), err);
+    /// ```
+    pub fn place_poles(
+        &self,
+        desired: &[Complex<T>],
+    ) -> Result<SMatrix<T, 1, NX>, StateSpaceError> {
+        if desired.len() != NX {
+            return Err(StateSpaceError::DimensionMismatch);
+        }
+        if NX == 0 {
+            return Ok(SMatrix::zeros());
+        }
+        let w_inv = self
+            .checked_ctrb()?
+            .try_inverse()
+            .ok_or(StateSpaceError::NotControllable)?;
+
+        // Coefficients alpha1, ..., alphan of phi(s).
+        let alpha_re: SVector<T, NX> = real_poly_from_roots(desired)?;
+
+        // phi(A) with Horner's method, ((A + alpha1 I) A + alpha2 I) A + ...
+        let eye: SMatrix<T, NX, NX> = SMatrix::identity();
+        let mut phi = eye.clone();
+        for a_k in alpha_re.iter() {
+            phi = phi * &self.a + &eye * a_k.clone();
+        }
+
+        Ok(w_inv.row(NX - 1) * phi)
+    }
+}
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: RealField + NumCast,
+{
+    /// Optimal state feedback gain `K = R^-1 B^T P` of the linear quadratic regulator, which
+    /// minimizes `integral(x^T Q x + u^T R u) dt` with `u = -K x`. `P` is the stabilizing
+    /// solution of the continuous algebraic Riccati equation
+    /// `A^T P + P A - P B R^-1 B^T P + Q = 0`, the same as MATLAB's `lqr()`. See
+    /// `QuadraticCost` to check the cost of a run.
+    ///
+    /// The Riccati equation is solved from the stable invariant subspace of the Hamiltonian
+    /// matrix `[A, -B R^-1 B^T; -Q, -A^T]`, found with the matrix sign function.
+    ///
+    /// Returns an error if:
+    /// * `Q` or `R` is not symmetric, `StateSpaceError::NotSymmetric`, see `is_symmetric()`.
+    /// * `R` is not positive definite, `StateSpaceError::NotPositiveDefinite`.
+    /// * The system is discrete, `StateSpaceError::SystemTimeMismatch`. Only the continuous
+    ///   Riccati equation is solved.
+    /// * There is no stabilizing solution, `StateSpaceError::NotStabilizable`. This happens when
+    ///   an unstable state can not be controlled, or when `Q` does not penalize a state on the
+    ///   imaginary axis.
+    ///
+    /// This example is the double integrator with `Q = I` and `R = 1`, which has the analytic
+    /// solution `K = [1, sqrt(3)]`.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, StateSpaceError};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; 0.0, 0.0])
+    ///     .set_b(matrix![0.0; 1.0])
+    ///     .set_c(matrix![1.0, 0.0]);
+    ///
+    /// let k = sys.lqr(&matrix![1.0, 0.0; 0.0, 1.0], &matrix![1.0]).unwrap();
+    /// assert!((k - matrix![1.0, 3.0_f64.sqrt()]).norm() < 1e-9);
+    ///
+    /// let mut closed_loop = sys;
+    /// closed_loop.set_a(sys.get_a() - sys.get_b() * k);
+    /// assert!(closed_loop.is_stable());
+    ///
+    /// // The unstable second state is not reachable from the input.
+    /// sys.set_a(matrix![-1.0, 0.0; 0.0, 1.0]).set_b(matrix![1.0; 0.0]);
+    /// let err = sys.lqr(&matrix![1.0, 0.0; 0.0, 1.0], &matrix![1.0]).err();
+    /// assert_eq!(Some(StateSpaceError::NotStabilizable), err);
+    ///
+    /// // A weight which is not symmetric is a typo.
+    /// let err = sys.lqr(&matrix![1.0, 1.0; 0.0, 1.0], &matrix![1.0]).err();
+    /// assert_eq!(Some(StateSpaceError::NotSymmetric), err);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn lqr(
+        &self,
+        q: &SMatrix<T, NX, NX>,
+        r: &SMatrix<T, NU, NU>,
+    ) -> Result<SMatrix<T, NU, NX>, StateSpaceError> {
+        if self.time == SystemTime::Discrete {
+            return Err(StateSpaceError::SystemTimeMismatch);
+        }
+        if !is_symmetric(q, symmetry_tol(q)) || !is_symmetric(r, symmetry_tol(r)) {
+            return Err(StateSpaceError::NotSymmetric);
+        }
+        if !is_positive_definite(r) {
+            return Err(StateSpaceError::NotPositiveDefinite);
+        }
+        let r_inv = r
+            .clone()
+            .try_inverse()
+            .ok_or(StateSpaceError::NotPositiveDefinite)?;
+
+        // The Hamiltonian, and the sign function which is -1 on its stable subspace [I; P].
+        let a = DMatrix::from_iterator(NX, NX, self.a.iter().cloned());
+        let g = &self.b * &r_inv * self.b.transpose();
+        let g = DMatrix::from_iterator(NX, NX, g.iter().cloned());
+        let q = DMatrix::from_iterator(NX, NX, q.iter().cloned());
+        let mut h = DMatrix::zeros(2 * NX, 2 * NX);
+        h.view_mut((0, 0), (NX, NX)).copy_from(&a);
+        h.view_mut((0, NX), (NX, NX)).copy_from(&(-g));
+        h.view_mut((NX, 0), (NX, NX)).copy_from(&(-q));
+        h.view_mut((NX, NX), (NX, NX)).copy_from(&(-a.transpose()));
+        let w = matrix_sign(h).ok_or(StateSpaceError::NotStabilizable)?;
+
+        // (W + I) [I; P] = 0, so [W12; W22 + I] P = -[W11 + I; W21], solved by least squares.
+        let eye = DMatrix::<T>::identity(NX, NX);
+        let mut lhs = DMatrix::zeros(2 * NX, NX);
+        lhs.view_mut((0, 0), (NX, NX))
+            .copy_from(&w.view((0, NX), (NX, NX)));
+        lhs.view_mut((NX, 0), (NX, NX))
+            .copy_from(&(w.view((NX, NX), (NX, NX)) + &eye));
+        let mut rhs = DMatrix::zeros(2 * NX, NX);
+        rhs.view_mut((0, 0), (NX, NX))
+            .copy_from(&(-(w.view((0, 0), (NX, NX)) + &eye)));
+        rhs.view_mut((NX, 0), (NX, NX))
+            .copy_from(&(-w.view((NX, 0), (NX, NX))));
+        let p = lhs
+            .svd(true, true)
+            .solve(&rhs, T::default_epsilon())
+            .map_err(|_| StateSpaceError::NotStabilizable)?;
+        let p: SMatrix<T, NX, NX> = SMatrix::from_iterator(p.iter().cloned());
+        let half = T::from(0.5).expect("Conversion failed");
+        let p = (p.clone() + p.transpose()) * half;
+
+        let k = r_inv * self.b.transpose() * p;
+        let mut closed_loop = self.clone();
+        closed_loop.a = &self.a - &self.b * &k;
+        if !closed_loop.is_stable() {
+            return Err(StateSpaceError::NotStabilizable);
+        }
+        Ok(k)
+    }
+
+    /// The observer based compensator of the LQG form, from a state feedback gain `K`, for
+    /// example from `lqr()` or `place_poles()`, and an observer gain `L`, see `Observer`. The
+    /// compensator estimates the state from the plant output and feeds back the estimate:
+    /// * `x_hat' = (A - BK - LC + LDK) x_hat + L y`
+    /// * `u = -K x_hat`
+    ///
+    /// The `LDK` term is zero for a strictly proper plant. The input of the compensator is the
+    /// plant output `y`, and its output is the plant input `u`, so it closes the loop with
+    /// `feedback(&plant, &compensator, FeedbackSign::Positive)`. By the separation principle,
+    /// the closed loop poles are the eigenvalues of `A - BK` and of `A - LC`. The compensator
+    /// has the `dt` and system time of the plant, and default bounds.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, vector};
+    /// use state_space::{feedback, FeedbackSign, StateSpace};
+    ///
+    /// // The double integrator, with the poles of A - BK at -1, -2 and of A - LC at -2, -3.
+    /// let mut plant: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// plant
+    ///     .set_a(matrix![0.0, 1.0; 0.0, 0.0])
+    ///     .set_b(matrix![0.0; 1.0])
+    ///     .set_c(matrix![1.0, 0.0]);
+    /// let k = matrix![2.0, 3.0];
+    /// let l = matrix![5.0; 6.0];
+    ///
+    /// let compensator = plant.compensator(&k, &l);
+    /// let a = plant.get_a() - plant.get_b() * k - l * plant.get_c();
+    /// assert_eq!(a, compensator.get_a());
+    /// assert_eq!(a.complex_eigenvalues(), compensator.eigenvalues());
+    ///
+    /// // (s^2 + 3s + 2)(s^2 + 5s + 6) = s^4 + 8s^3 + 23s^2 + 28s + 12
+    /// let closed_loop: StateSpace<f64, 1, 4, 1> =
+    ///     feedback(&plant, &compensator, FeedbackSign::Positive).unwrap();
+    /// assert!((closed_loop.char_poly() - vector![8.0, 23.0, 28.0, 12.0]).norm() < 1e-9);
+    /// ```
+    pub fn compensator(
+        &self,
+        k: &SMatrix<T, NU, NX>,
+        l: &SMatrix<T, NX, NY>,
+    ) -> StateSpace<T, NY, NX, NU> {
+        let a = &self.a - &self.b * k - l * &self.c + l * &self.d * k;
+        let mut compensator = StateSpace::new();
+        compensator
+            .set_a(a)
+            .set_b(l.clone())
+            .set_c(-k.clone())
+            .set_dt(self.dt.clone());
+        compensator.time = self.time;
+        compensator
+    }
+}
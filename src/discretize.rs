@@ -0,0 +1,265 @@
+/*!
+# discretize
+The `discretize` module converts a continuous `StateSpace` into an equivalent discrete time
+system. The returned system is flagged as `SystemTime::Discrete`, so `update()` steps it with
+`x(n+1) = Ax + Bu` rather than integrating.
+
+## Features.
+* `to_discrete()` is the exact zero-order-hold (ZOH) discretization, the same as MATLAB's
+  `c2d(sys, dt, 'zoh')`.
+* `to_tustin()` is the bilinear (trapezoidal) discretization, the same as MATLAB's
+  `c2d(sys, dt, 'tustin')`.
+* `to_foh()` is the first-order-hold (FOH) discretization, the same as MATLAB's
+  `c2d(sys, dt, 'foh')`. It uses the exponential of a dynamically sized augmented matrix, so it
+  requires the `std` feature.
+*/
+
+#[cfg(feature = "std")]
+use na::DMatrix;
+use na::{RealField, SMatrix};
+use nalgebra as na;
+use num_traits::NumCast;
+
+#[cfg(feature = "std")]
+use crate::SysVec;
+use crate::{StateSpace, StateSpaceError, SystemTime};
+
+// Number of Taylor series terms used for the matrix exponential. The scaling step keeps the norm
+// of A*h below 0.5, so the truncation error of 12 terms is well below f64 precision.
+const TAYLOR_TERMS: usize = 12;
+
+// The most squarings of the scaling step, enough for the norm of any finite f64 matrix.
+const MAX_SQUARINGS: usize = 1100;
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: RealField + NumCast,
+{
+    /// Exact zero-order-hold discretization using the current `dt`. The discrete matrices are
+    /// `Ad = exp(A*dt)` and `Bd = A^{-1}(Ad - I)B`, while C and D are unchanged.
+    ///
+    /// Rather than inverting A, `Bd` is computed from the series `sum(A^k dt^(k+1) / (k+1)!) B`,
+    /// which is equal to the formula above and also valid when A is singular (integrators).
+    /// The series is evaluated with scaling and squaring, so a large `A*dt` is handled
+    /// accurately. A system which is already discrete is returned unchanged.
+    ///
+    /// Panics if an entry of A, or `A*dt`, is not finite, since the scaling would not end.
+    ///
+    /// This example discretizes the second order system of Example 2. The states of a ZOH
+    /// system match the analytic step response exactly at each sample.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, SysVec, SystemTime};
+    ///
+    /// let w = 2.0 * std::f64::consts::PI;
+    /// let z = 0.707_f64;
+    /// let dt = 0.1;
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; -w * w, -2.0 * z * w])
+    ///     .set_b(matrix![0.0; w * w])
+    ///     .set_c(matrix![1.0, 0.0])
+    ///     .set_dt(dt);
+    ///
+    /// let mut sys_d = sys.to_discrete();
+    /// assert_eq!(SystemTime::Discrete, sys_d.get_system_time());
+    /// sys_d.set_u(SysVec::from_val(1.0));
+    ///
+    /// // Analytic step response of an underdamped second order system.
+    /// let wd = w * (1.0 - z * z).sqrt();
+    /// let step = |t: f64| {
+    ///     1.0 - (-z * w * t).exp() / (1.0 - z * z).sqrt() * (wd * t + z.acos()).sin()
+    /// };
+    ///
+    /// for k in 1..=20 {
+    ///     sys_d.update();
+    ///     assert!((sys_d.get_x()[0] - step(k as f64 * dt)).abs() < 1e-9);
+    /// }
+    /// ```
+    ///
+    /// ```rust,should_panic
+    /// use nalgebra::matrix;
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![f64::NAN]);
+    /// sys.to_discrete();
+    /// ```
+    pub fn to_discrete(&self) -> Self {
+        if self.is_discrete() {
+            return self.clone();
+        }
+
+        // Scale the step so that ||A*h|| <= 0.5, h = dt / 2^squarings.
+        let half = T::from(0.5).expect("Conversion failed");
+        let mut h = self.dt.clone();
+        let mut norm = self.a.norm() * h.clone();
+        assert!(norm.is_finite(), "A*dt must be finite");
+        let mut squarings = 0;
+        while norm > half && squarings < MAX_SQUARINGS {
+            norm *= half.clone();
+            h *= half.clone();
+            squarings += 1;
+        }
+
+        // Taylor series for phi = exp(A*h) and gamma = integral of exp(A*t) from 0 to h.
+        let ah = self.a.clone() * h.clone();
+        let mut term: SMatrix<T, NX, NX> = SMatrix::identity();
+        let mut phi = term.clone();
+        let mut gamma = term.clone() * h.clone();
+        for k in 1..=TAYLOR_TERMS {
+            let kt = T::from(k).expect("Conversion failed");
+            term = (term * ah.clone()) / kt.clone();
+            phi += term.clone();
+            gamma += term.clone() * (h.clone() / (kt + T::one()));
+        }
+
+        // Undo the scaling, phi(2h) = phi(h)^2 and gamma(2h) = gamma(h) + phi(h) * gamma(h).
+        for _ in 0..squarings {
+            gamma = gamma.clone() + phi.clone() * gamma;
+            phi = phi.clone() * phi;
+        }
+
+        let mut sys = self.clone();
+        sys.a = phi;
+        sys.b = gamma * self.b.clone();
+        sys.time = SystemTime::Discrete;
+        sys
+    }
+
+    /// Tustin (bilinear) discretization using the current `dt`. With `M = I - A*dt/2` the
+    /// discrete matrices are:
+    /// * `Ad = M^{-1}(I + A*dt/2)`
+    /// * `Bd = M^{-1} * B * dt`
+    /// * `Cd = C * M^{-1}`
+    /// * `Dd = D + Cd * B * dt/2`
+    ///
+    /// Returns `StateSpaceError::SingularMatrix` if `M` can not be inverted. A system which is
+    /// already discrete is returned unchanged.
+    ///
+    /// The Tustin transform preserves the DC gain. Here a continuous lag with a DC gain of 1.5
+    /// is discretized and stepped until it settles.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![-2.0])
+    ///     .set_b(matrix![3.0])
+    ///     .set_c(matrix![1.0])
+    ///     .set_dt(0.1);
+    ///
+    /// let mut sys_d = sys.to_tustin().unwrap();
+    /// sys_d.set_u(SysVec::from_val(1.0));
+    /// for _ in 0..200 {
+    ///     sys_d.update();
+    /// }
+    /// assert!((sys_d.get_y()[0] - 1.5).abs() < 1e-9);
+    /// ```
+    pub fn to_tustin(&self) -> Result<Self, StateSpaceError> {
+        if self.is_discrete() {
+            return Ok(self.clone());
+        }
+
+        let half_dt = self.dt.clone() * T::from(0.5).expect("Conversion failed");
+        let eye: SMatrix<T, NX, NX> = SMatrix::identity();
+        let m_inv = (eye.clone() - self.a.clone() * half_dt.clone())
+            .try_inverse()
+            .ok_or(StateSpaceError::SingularMatrix)?;
+
+        let mut sys = self.clone();
+        sys.a = m_inv.clone() * (eye + self.a.clone() * half_dt.clone());
+        sys.b = m_inv.clone() * self.b.clone() * self.dt.clone();
+        sys.c = self.c.clone() * m_inv;
+        sys.d = self.d.clone() + sys.c.clone() * self.b.clone() * half_dt;
+        sys.time = SystemTime::Discrete;
+        Ok(sys)
+    }
+
+    /// First-order-hold discretization using the current `dt`. The input is interpolated with a
+    /// straight line between samples, rather than held as by `to_discrete()`, which is more
+    /// accurate for a smoothly varying input. The integrals are found from the exponential of
+    /// the augmented matrix
+    /// ```text
+    ///     | A  B  0      |          | Phi  G1  G2 |
+    /// exp(| 0  0  I / dt | * dt) =  | 0    I   I  |
+    ///     | 0  0  0      |          | 0    0   I  |
+    /// ```
+    /// so `x(n+1) = Phi x(n) + G1 u(n) + G2 (u(n+1) - u(n))`. This depends on the next input, so
+    /// the state of the returned system is `xi = x - G2 u`, which gives:
+    /// * `Ad = Phi`
+    /// * `Bd = G1 + (Phi - I) G2`
+    /// * `Cd = C`
+    /// * `Dd = D + C G2`
+    ///
+    /// The outputs are those of the continuous system at the samples, but the state is `xi`
+    /// rather than `x`, so it starts at zero with the default bounds. A system which is already
+    /// discrete is returned unchanged.
+    ///
+    /// This example samples a sine through the lag `1 / (s + 1)`. After the transient, the FOH
+    /// system follows the exact response `sin(t - pi/4) / sqrt(2)`, while the ZOH system
+    /// follows it half a sample late, the average delay of holding each sample.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, vector};
+    /// use std::f64::consts::{FRAC_PI_4, SQRT_2};
+    /// use state_space::StateSpace;
+    ///
+    /// let dt = 0.1;
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![-1.0]).set_b(matrix![1.0]).set_c(matrix![1.0]).set_dt(dt);
+    /// let mut foh = sys.to_foh();
+    /// let mut zoh = sys.to_discrete();
+    /// let exact = |t: f64| (t - FRAC_PI_4).sin() / SQRT_2;
+    ///
+    /// let (mut err_foh, mut err_zoh, mut err_zoh_delayed) = (0.0_f64, 0.0_f64, 0.0_f64);
+    /// for k in 0..1000 {
+    ///     let t = k as f64 * dt;
+    ///     let y_foh = foh.step(vector![t.sin()])[0];
+    ///     let y_zoh = zoh.step(vector![t.sin()])[0];
+    ///     if k >= 500 {
+    ///         err_foh = err_foh.max((y_foh - exact(t)).abs());
+    ///         err_zoh = err_zoh.max((y_zoh - exact(t)).abs());
+    ///         err_zoh_delayed = err_zoh_delayed.max((y_zoh - exact(t - dt / 2.0)).abs());
+    ///     }
+    /// }
+    /// assert!(err_foh < 1e-3);
+    /// assert!(err_zoh > 0.03);
+    /// assert!(err_zoh_delayed < 1e-3);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn to_foh(&self) -> Self {
+        if self.is_discrete() {
+            return self.clone();
+        }
+
+        let n = NX + 2 * NU;
+        let mut m: DMatrix<T> = DMatrix::zeros(n, n);
+        let b = DMatrix::from_iterator(NX, NU, self.b.iter().cloned());
+        let a = DMatrix::from_iterator(NX, NX, self.a.iter().cloned());
+        m.view_mut((0, 0), (NX, NX))
+            .copy_from(&(a * self.dt.clone()));
+        m.view_mut((0, NX), (NX, NU))
+            .copy_from(&(b * self.dt.clone()));
+        m.view_mut((NX, NX + NU), (NU, NU)).fill_with_identity();
+        let e = m.exp();
+
+        let phi: SMatrix<T, NX, NX> =
+            SMatrix::from_iterator(e.view((0, 0), (NX, NX)).iter().cloned());
+        let g1: SMatrix<T, NX, NU> =
+            SMatrix::from_iterator(e.view((0, NX), (NX, NU)).iter().cloned());
+        let g2: SMatrix<T, NX, NU> =
+            SMatrix::from_iterator(e.view((0, NX + NU), (NX, NU)).iter().cloned());
+
+        let eye: SMatrix<T, NX, NX> = SMatrix::identity();
+        let mut sys = self.clone();
+        sys.b = g1 + (phi.clone() - eye) * &g2;
+        sys.d = self.d.clone() + self.c.clone() * g2;
+        sys.a = phi;
+        sys.x = SysVec::new();
+        sys.state_names = None;
+        sys.time = SystemTime::Discrete;
+        sys
+    }
+}
@@ -0,0 +1,196 @@
+/*!
+# display
+The `display` module implements `Display` for `StateSpace`, a readable summary of a system for
+debugging, in place of the derived `Debug` output.
+
+## Features.
+* The A, B, C, and D matrices are printed as blocks with their sizes and aligned columns.
+* `dt`, the system time, and `u`, `x`, and `y` with their bounds are printed after the matrices.
+* Channels are labelled with their names when they are set, see `set_input_names()`.
+* A precision is passed on to every number, `format!("{:.3}", sys)`.
+
+Bounds at the default of +/-9e99 are printed as `-inf` and `inf`.
+*/
+
+use core::fmt;
+
+use na::SMatrix;
+use nalgebra as na;
+use num_traits::NumCast;
+
+use crate::{StateSpace, SysVec, SystemTime};
+
+// Counts the characters written, to find column widths without allocating a String.
+struct Counter(usize);
+
+impl fmt::Write for Counter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.chars().count();
+        Ok(())
+    }
+}
+
+// Writes v right aligned to width, with the precision of the formatter if there is one.
+fn write_value<W: fmt::Write, T: fmt::Display>(
+    w: &mut W,
+    v: &T,
+    precision: Option<usize>,
+    width: usize,
+) -> fmt::Result {
+    match precision {
+        Some(p) => write!(w, "{:>width$.p$}", v),
+        None => write!(w, "{:>width$}", v),
+    }
+}
+
+fn width<T: fmt::Display>(v: &T, precision: Option<usize>) -> usize {
+    let mut counter = Counter(0);
+    let _ = write_value(&mut counter, v, precision, 0);
+    counter.0
+}
+
+fn write_matrix<T: fmt::Display, const R: usize, const C: usize>(
+    f: &mut fmt::Formatter<'_>,
+    name: &str,
+    m: &SMatrix<T, R, C>,
+) -> fmt::Result {
+    let precision = f.precision();
+    writeln!(f, "{} ({}x{}):", name, R, C)?;
+    let w = m.iter().map(|v| width(v, precision)).max().unwrap_or(0);
+    for i in 0..R {
+        write!(f, "  [")?;
+        for j in 0..C {
+            write!(f, " ")?;
+            write_value(f, &m[(i, j)], precision, w)?;
+        }
+        writeln!(f, " ]")?;
+    }
+    Ok(())
+}
+
+// A bound of the system vectors, "inf" when it is at the default of +/-9e99.
+struct Bound<'a, T>(&'a T, bool);
+
+impl<T: fmt::Display + PartialOrd + NumCast> fmt::Display for Bound<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (v, upper) = (self.0, self.1);
+        let unbounded = match T::from(if upper { 9e99 } else { -9e99 }) {
+            Some(default) => (upper && *v >= default) || (!upper && *v <= default),
+            None => false,
+        };
+        match (unbounded, upper) {
+            (true, true) => f.pad("inf"),
+            (true, false) => f.pad("-inf"),
+            _ => fmt::Display::fmt(v, f),
+        }
+    }
+}
+
+fn write_sys_vec<T, const N: usize>(
+    f: &mut fmt::Formatter<'_>,
+    name: &str,
+    v: &SysVec<T, N>,
+    names: Option<[&'static str; N]>,
+) -> fmt::Result
+where
+    T: nalgebra::Scalar + fmt::Display + PartialOrd + NumCast,
+{
+    let precision = f.precision();
+    let (val, lb, ub) = (v.val_ref(), v.get_lb(), v.get_ub());
+    writeln!(f, "{} ({}), value [lower, upper]:", name, N)?;
+
+    // Column widths, the channel label is its name or its index.
+    let label_w = (0..N)
+        .map(|i| match names {
+            Some(names) => names[i].chars().count(),
+            None => width(&i, None) + 2,
+        })
+        .max()
+        .unwrap_or(0);
+    let val_w = val.iter().map(|v| width(v, precision)).max().unwrap_or(0);
+    let lb_w = lb
+        .iter()
+        .map(|v| width(&Bound(v, false), precision))
+        .max()
+        .unwrap_or(0);
+    let ub_w = ub
+        .iter()
+        .map(|v| width(&Bound(v, true), precision))
+        .max()
+        .unwrap_or(0);
+
+    for i in 0..N {
+        match names {
+            Some(names) => write!(f, "  {:<label_w$}  ", names[i])?,
+            None => write!(
+                f,
+                "  [{}]{:pad$}  ",
+                i,
+                "",
+                pad = label_w - width(&i, None) - 2
+            )?,
+        }
+        write_value(f, &val[i], precision, val_w)?;
+        write!(f, " [")?;
+        write_value(f, &Bound(&lb[i], false), precision, lb_w)?;
+        write!(f, ", ")?;
+        write_value(f, &Bound(&ub[i], true), precision, ub_w)?;
+        writeln!(f, "]")?;
+    }
+    Ok(())
+}
+
+/// Prints the matrices, `dt`, and the system vectors.
+///
+/// ```rust
+/// use nalgebra::matrix;
+/// use state_space::{StateSpace, SysVec};
+///
+/// let mut sys: StateSpace<f64, 2, 2, 1> = StateSpace::new();
+/// sys.set_a(matrix![-1.0, 0.0; 0.0, -2.5])
+///     .set_b(matrix![1.0, 0.0; 0.0, 1.0])
+///     .set_c(matrix![1.0, 1.0])
+///     .set_u(SysVec::from_scalars(0.5, -1.0, 1.0))
+///     .set_dt(0.1);
+/// sys.set_input_names(&["throttle", "flap"]).unwrap();
+///
+/// let text = format!("{}", sys);
+/// assert!(text.starts_with("StateSpace (NU = 2, NX = 2, NY = 1), continuous, dt = 0.1"));
+/// assert!(text.contains("A (2x2):\n  [   -1    0 ]\n  [    0 -2.5 ]\n"));
+/// assert!(text.contains("B (2x2):"));
+/// assert!(text.contains("C (1x2):"));
+/// assert!(text.contains("D (1x2):"));
+/// assert!(text.contains("u (2), value [lower, upper]:\n  throttle  0.5 [-1, 1]\n"));
+/// assert!(text.contains("x (2), value [lower, upper]:\n  [0]  0 [-inf, inf]\n"));
+///
+/// // The precision applies to every number.
+/// let text = format!("{:.2}", sys);
+/// assert!(text.contains("  [ -1.00  0.00 ]\n"));
+/// ```
+impl<T, const NU: usize, const NX: usize, const NY: usize> fmt::Display
+    for StateSpace<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar + fmt::Display + PartialOrd + NumCast,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let time = match self.time {
+            SystemTime::Continuous => "continuous",
+            SystemTime::Discrete => "discrete",
+        };
+        write!(
+            f,
+            "StateSpace (NU = {}, NX = {}, NY = {}), {}, dt = ",
+            NU, NX, NY, time
+        )?;
+        write_value(f, &self.dt, f.precision(), 0)?;
+        writeln!(f)?;
+
+        write_matrix(f, "A", &self.a)?;
+        write_matrix(f, "B", &self.b)?;
+        write_matrix(f, "C", &self.c)?;
+        write_matrix(f, "D", &self.d)?;
+        write_sys_vec(f, "u", &self.u, self.input_names)?;
+        write_sys_vec(f, "x", &self.x, self.state_names)?;
+        write_sys_vec(f, "y", &self.y, self.output_names)
+    }
+}
@@ -0,0 +1,211 @@
+/*!
+# dynamic
+The `dynamic` module contains `DynStateSpace`, a copy of a `StateSpace` system with dynamically
+sized `DMatrix` and `DVector` fields, for code where the size is not known at compile time, such
+as an FFI or plugin boundary.
+
+## Features.
+* `StateSpace::into_dynamic()` and `DynStateSpace::from_static()` convert a system.
+* `update()` steps the system as `StateSpace::update()` does, with the bounds of `u`, `x`, and `y`.
+* `set_u()` and `set_x()` check the length of the vector.
+
+The matrices, `dt`, the system time, the values, and the bounds are copied. The integrator is
+forward Euler for a continuous system, and the rate limit, anti-windup, output scale, and state
+feedback of the `StateSpace` are not copied. The matrices are allocated, so this module requires
+the `std` feature.
+*/
+
+use na::{DMatrix, DVector};
+use nalgebra as na;
+use num_traits::{NumCast, One, Zero};
+
+use crate::{StateSpace, StateSpaceError, SystemTime};
+
+/// A state space system with dynamically sized matrices, see `StateSpace::into_dynamic()`.
+///
+/// ```rust
+/// use nalgebra::{dvector, matrix};
+/// use state_space::{DynStateSpace, StateSpace, SysVec};
+///
+/// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+/// sys.set_a(matrix![0.0, 1.0; -4.0, -2.0])
+///     .set_b(matrix![0.0; 4.0])
+///     .set_c(matrix![1.0, 0.0])
+///     .set_y(SysVec::from_scalars(0.0, -1.0, 0.9))
+///     .set_dt(0.01);
+///
+/// let mut dynamic = sys.into_dynamic();
+/// assert_eq!((1, 2, 1), (dynamic.nu(), dynamic.nx(), dynamic.ny()));
+///
+/// // The same step response, including the bound on y.
+/// sys.set_u(SysVec::from_val(1.0));
+/// dynamic.set_u(dvector![1.0]).unwrap();
+/// for _ in 0..500 {
+///     sys.update();
+///     dynamic.update();
+///     assert_eq!(sys.get_x().as_slice(), dynamic.get_x().as_slice());
+///     assert_eq!(sys.get_y().as_slice(), dynamic.get_y().as_slice());
+/// }
+/// assert_eq!(0.9, dynamic.get_y()[0]);
+///
+/// // The length of the input is checked at run time.
+/// assert!(dynamic.set_u(dvector![1.0, 2.0]).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DynStateSpace<T: na::Scalar> {
+    a: DMatrix<T>,
+    b: DMatrix<T>,
+    c: DMatrix<T>,
+    d: DMatrix<T>,
+    u: DVector<T>,
+    x: DVector<T>,
+    y: DVector<T>,
+    u_bounds: (DVector<T>, DVector<T>),
+    x_bounds: (DVector<T>, DVector<T>),
+    y_bounds: (DVector<T>, DVector<T>),
+    dt: T,
+    time: SystemTime,
+}
+
+impl<T> DynStateSpace<T>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    /// Copies `sys` into a dynamically sized system, see the module documentation for what is
+    /// copied.
+    pub fn from_static<const NU: usize, const NX: usize, const NY: usize>(
+        sys: &StateSpace<T, NU, NX, NY>,
+    ) -> Self {
+        Self {
+            a: DMatrix::from_iterator(NX, NX, sys.a.iter().cloned()),
+            b: DMatrix::from_iterator(NX, NU, sys.b.iter().cloned()),
+            c: DMatrix::from_iterator(NY, NX, sys.c.iter().cloned()),
+            d: DMatrix::from_iterator(NY, NU, sys.d.iter().cloned()),
+            u: DVector::from_iterator(NU, sys.u.val_ref().iter().cloned()),
+            x: DVector::from_iterator(NX, sys.x.val_ref().iter().cloned()),
+            y: DVector::from_iterator(NY, sys.y.val_ref().iter().cloned()),
+            u_bounds: (
+                DVector::from_iterator(NU, sys.u.get_lb().iter().cloned()),
+                DVector::from_iterator(NU, sys.u.get_ub().iter().cloned()),
+            ),
+            x_bounds: (
+                DVector::from_iterator(NX, sys.x.get_lb().iter().cloned()),
+                DVector::from_iterator(NX, sys.x.get_ub().iter().cloned()),
+            ),
+            y_bounds: (
+                DVector::from_iterator(NY, sys.y.get_lb().iter().cloned()),
+                DVector::from_iterator(NY, sys.y.get_ub().iter().cloned()),
+            ),
+            dt: sys.dt.clone(),
+            time: sys.time,
+        }
+    }
+
+    /// Steps the system by `dt`, in the same order as `StateSpace::update()`: `u` and `x` are
+    /// clamped, `y(n) = Cx(n) + Du(n)` is stored clamped, and `x(n+1)` is integrated with
+    /// forward Euler, or stepped with A for a discrete system, and clamped.
+    pub fn update(&mut self) -> &mut Self {
+        clamp(&mut self.u, &self.u_bounds);
+        clamp(&mut self.x, &self.x_bounds);
+
+        let yn = &self.c * &self.x + &self.d * &self.u;
+        let x1 = match self.time {
+            SystemTime::Continuous => {
+                let x_dot = &self.a * &self.x + &self.b * &self.u;
+                &self.x + x_dot * self.dt.clone()
+            }
+            SystemTime::Discrete => &self.a * &self.x + &self.b * &self.u,
+        };
+
+        self.x = x1;
+        clamp(&mut self.x, &self.x_bounds);
+        self.y = yn;
+        clamp(&mut self.y, &self.y_bounds);
+        self
+    }
+
+    /// Sets the value of `u`. Returns `StateSpaceError::DimensionMismatch` if the length is not
+    /// `nu()`.
+    pub fn set_u(&mut self, u: DVector<T>) -> Result<&mut Self, StateSpaceError> {
+        if u.len() != self.nu() {
+            return Err(StateSpaceError::DimensionMismatch);
+        }
+        self.u = u;
+        Ok(self)
+    }
+
+    /// Sets the value of `x`. Returns `StateSpaceError::DimensionMismatch` if the length is not
+    /// `nx()`.
+    pub fn set_x(&mut self, x: DVector<T>) -> Result<&mut Self, StateSpaceError> {
+        if x.len() != self.nx() {
+            return Err(StateSpaceError::DimensionMismatch);
+        }
+        self.x = x;
+        Ok(self)
+    }
+
+    pub fn get_u(&self) -> DVector<T> {
+        self.u.clone()
+    }
+
+    pub fn get_x(&self) -> DVector<T> {
+        self.x.clone()
+    }
+
+    pub fn get_y(&self) -> DVector<T> {
+        self.y.clone()
+    }
+
+    pub fn get_dt(&self) -> T {
+        self.dt.clone()
+    }
+
+    pub fn get_system_time(&self) -> SystemTime {
+        self.time
+    }
+
+    /// The number of inputs.
+    pub fn nu(&self) -> usize {
+        self.b.ncols()
+    }
+
+    /// The number of states.
+    pub fn nx(&self) -> usize {
+        self.a.nrows()
+    }
+
+    /// The number of outputs.
+    pub fn ny(&self) -> usize {
+        self.c.nrows()
+    }
+}
+
+// Clamps each element of v to its (lower, upper) bounds.
+fn clamp<T: na::Scalar + PartialOrd>(v: &mut DVector<T>, bounds: &(DVector<T>, DVector<T>)) {
+    for ((v, lb), ub) in v.iter_mut().zip(bounds.0.iter()).zip(bounds.1.iter()) {
+        *v = na::clamp(v.clone(), lb.clone(), ub.clone());
+    }
+}
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    /// Converts to a `DynStateSpace`, with dynamically sized matrices, see
+    /// `DynStateSpace::from_static()`.
+    pub fn into_dynamic(&self) -> DynStateSpace<T> {
+        DynStateSpace::from_static(self)
+    }
+}
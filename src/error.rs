@@ -0,0 +1,113 @@
+/*!
+# error
+The `error` module contains `StateSpaceError`, the error type returned by the fallible methods of
+this crate.
+
+## Panics.
+Methods return a `StateSpaceError` for a failure which depends on the data, such as a singular
+matrix, an uncontrollable system, or recorded data of the wrong length. The few methods which
+still panic have a fallible version, or only panic on a programming error:
+* `StateSpace::set_dt()` panics for a `dt` which is not positive, `try_set_dt()` returns
+  `StateSpaceError::NonPositiveDt`.
+* `SysVec::new()`, `from_val()`, and `from_scalars()` panic when the value can not be converted to
+  `T`, `try_new()`, `try_from_val()`, and `try_from_scalars()` return
+  `StateSpaceError::ConversionFailed`.
+* `step_response()` and `impulse_response()` panic for a channel which is out of range, the same
+  as indexing a vector.
+* `to_discrete()` panics for an `A` or `dt` which is not finite, and so does
+  `KalmanFilter::new()` for a continuous system.
+* Internal conversions of constants such as `0.5` to `T` panic with "Conversion failed". They
+  can not fail for `f32` and `f64`.
+*/
+
+use core::fmt;
+
+/// Errors returned by the fallible methods of `StateSpace` and `SysVec`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum StateSpaceError {
+    /// A matrix which must be inverted is singular.
+    SingularMatrix,
+    /// A const generic dimension does not match the size required by the operation.
+    DimensionMismatch,
+    /// Two systems which are combined have a different `dt`.
+    DtMismatch,
+    /// Two systems which are combined are not both continuous or both discrete.
+    SystemTimeMismatch,
+    /// A value could not be converted to the scalar type of the system with `NumCast`.
+    ConversionFailed,
+    /// An element index is larger than the length of the vector.
+    IndexOutOfRange,
+    /// A required field of a builder was never set. Contains the name of the field.
+    MissingField(&'static str),
+    /// The time step `dt` is zero or negative.
+    NonPositiveDt,
+    /// A polynomial is empty or its leading coefficient is zero.
+    InvalidPolynomial,
+    /// A transfer function has a numerator of higher degree than its denominator.
+    NotProper,
+    /// The operation requires a stable system, see `StateSpace::is_stable()`.
+    NotStable,
+    /// The operation requires a minimal system, with every state controllable and observable.
+    NotMinimal,
+    /// A matrix which must be positive definite is not.
+    NotPositiveDefinite,
+    /// The operation requires a controllable system, with every state reachable from the input.
+    NotControllable,
+    /// The operation requires a stabilizable system, with every unstable state controllable.
+    NotStabilizable,
+    /// A matrix has a NaN or infinite element.
+    NonFinite,
+    /// The scheduling points of a `GainScheduler` are not strictly increasing.
+    InvalidSchedule,
+    /// A lower bound is larger than the upper bound of the same element.
+    InvalidBounds,
+    /// A line of text could not be read or parsed. Contains the line number, starting at 1.
+    Parse(usize),
+    /// A matrix which must be symmetric is not.
+    NotSymmetric,
+    /// The timestamps of a timeseries are not strictly increasing, or its end time is negative
+    /// or not finite.
+    InvalidTimes,
+}
+
+impl fmt::Display for StateSpaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateSpaceError::SingularMatrix => write!(f, "matrix is singular"),
+            StateSpaceError::DimensionMismatch => write!(f, "dimensions do not match"),
+            StateSpaceError::DtMismatch => write!(f, "systems have a different dt"),
+            StateSpaceError::SystemTimeMismatch => {
+                write!(f, "systems are not both continuous or both discrete")
+            }
+            StateSpaceError::ConversionFailed => write!(f, "numeric conversion failed"),
+            StateSpaceError::IndexOutOfRange => write!(f, "index is out of range"),
+            StateSpaceError::MissingField(name) => write!(f, "field `{}` was never set", name),
+            StateSpaceError::NonPositiveDt => write!(f, "dt must be positive"),
+            StateSpaceError::InvalidPolynomial => {
+                write!(f, "polynomial is empty or has a zero leading coefficient")
+            }
+            StateSpaceError::NotProper => write!(f, "transfer function is not proper"),
+            StateSpaceError::NotStable => write!(f, "system is not stable"),
+            StateSpaceError::NotMinimal => write!(f, "system is not minimal"),
+            StateSpaceError::NotPositiveDefinite => write!(f, "matrix is not positive definite"),
+            StateSpaceError::NotControllable => write!(f, "system is not controllable"),
+            StateSpaceError::NotStabilizable => write!(f, "system is not stabilizable"),
+            StateSpaceError::NonFinite => write!(f, "matrix has a NaN or infinite element"),
+            StateSpaceError::InvalidSchedule => {
+                write!(f, "scheduling points are not strictly increasing")
+            }
+            StateSpaceError::InvalidBounds => write!(f, "lower bound is larger than upper bound"),
+            StateSpaceError::Parse(line) => write!(f, "line {} could not be parsed", line),
+            StateSpaceError::NotSymmetric => write!(f, "matrix is not symmetric"),
+            StateSpaceError::InvalidTimes => {
+                write!(
+                    f,
+                    "timestamps are not increasing or the end time is invalid"
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for StateSpaceError {}
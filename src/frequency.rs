@@ -0,0 +1,107 @@
+/*!
+# frequency
+The `frequency` module evaluates the frequency response of a `StateSpace` system, the data
+behind a Bode diagram.
+
+## Features.
+* `freq_response()` returns `G(jw)` of a SISO system as one complex number per frequency.
+* `freq_response_mimo()` returns `G(jw)` of a MIMO system as one complex matrix per frequency.
+* A discrete system is evaluated on the unit circle, `G(z)` with `z = exp(jw dt)`.
+
+The results are collected in a `Vec`, so this module requires the `std` feature.
+*/
+
+use na::{Complex, RealField, SMatrix};
+use nalgebra as na;
+use num_traits::NumCast;
+
+use crate::{StateSpace, StateSpaceError, SystemTime};
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: RealField + NumCast,
+{
+    /// The frequency response `G(jw) = C (jwI - A)^{-1} B + D` at each frequency of `omegas`,
+    /// in radians per second. A discrete system uses `z = exp(jw dt)` in place of `jw`.
+    ///
+    /// Returns `StateSpaceError::SingularMatrix` if a frequency is exactly at a pole of the
+    /// system, for example `w = 0` for an integrator.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, Complex};
+    /// use state_space::StateSpace;
+    ///
+    /// // Two inputs and two outputs, each output is a first order lag of one input.
+    /// let mut sys: StateSpace<f64, 2, 2, 2> = StateSpace::new();
+    /// sys.set_a(matrix![-1.0, 0.0; 0.0, -10.0])
+    ///     .set_b(matrix![1.0, 0.0; 0.0, 10.0])
+    ///     .set_c(matrix![1.0, 0.0; 0.0, 1.0]);
+    ///
+    /// let g = sys.freq_response_mimo(&[10.0]).unwrap();
+    /// let expected = Complex::new(1.0, 0.0) / Complex::new(1.0, 1.0);
+    /// assert!((g[0][(1, 1)] - expected).norm() < 1e-12);
+    /// assert_eq!(Complex::new(0.0, 0.0), g[0][(0, 1)]);
+    /// ```
+    pub fn freq_response_mimo(
+        &self,
+        omegas: &[T],
+    ) -> Result<Vec<SMatrix<Complex<T>, NY, NU>>, StateSpaceError> {
+        let a = self.a.map(|v| Complex::new(v, T::zero()));
+        let b = self.b.map(|v| Complex::new(v, T::zero()));
+        let c = self.c.map(|v| Complex::new(v, T::zero()));
+        let d = self.d.map(|v| Complex::new(v, T::zero()));
+        let eye: SMatrix<Complex<T>, NX, NX> = SMatrix::identity();
+
+        omegas
+            .iter()
+            .map(|w| {
+                let s = match self.time {
+                    SystemTime::Continuous => Complex::new(T::zero(), w.clone()),
+                    SystemTime::Discrete => {
+                        let wt = w.clone() * self.dt.clone();
+                        Complex::new(wt.clone().cos(), wt.sin())
+                    }
+                };
+                let resolvent = (eye.clone() * s - a.clone())
+                    .try_inverse()
+                    .ok_or(StateSpaceError::SingularMatrix)?;
+                Ok(c.clone() * resolvent * b.clone() + d.clone())
+            })
+            .collect()
+    }
+}
+
+impl<T, const NX: usize> StateSpace<T, 1, NX, 1>
+where
+    T: RealField + NumCast,
+{
+    /// The frequency response of a SISO system, see `freq_response_mimo()`.
+    ///
+    /// A first order lag `1 / (s + 1)` is 3 dB down with a phase of -45 degrees at its corner
+    /// frequency of 1 rad/s.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![-1.0])
+    ///     .set_b(matrix![1.0])
+    ///     .set_c(matrix![1.0]);
+    ///
+    /// let g = sys.freq_response(&[0.0, 1.0, 100.0]).unwrap();
+    /// let db: Vec<f64> = g.iter().map(|g| 20.0 * g.norm().log10()).collect();
+    /// assert!(db[0].abs() < 1e-12);
+    /// assert!((db[1] + 3.0103).abs() < 1e-4);
+    /// assert!((g[1].arg().to_degrees() + 45.0).abs() < 1e-12);
+    /// assert!((db[2] + 40.0).abs() < 1e-3);
+    ///
+    /// // An integrator has a pole at w = 0.
+    /// sys.set_a(matrix![0.0]);
+    /// assert!(sys.freq_response(&[0.0]).is_err());
+    /// ```
+    pub fn freq_response(&self, omegas: &[T]) -> Result<Vec<Complex<T>>, StateSpaceError> {
+        let g = self.freq_response_mimo(omegas)?;
+        Ok(g.into_iter().map(|g| g[(0, 0)].clone()).collect())
+    }
+}
@@ -0,0 +1,161 @@
+/*!
+# gramian
+The `gramian` module computes the controllability and observability Gramians of a stable
+`StateSpace` system, which are the basis of balanced model reduction and the H2 norm.
+
+## Features.
+* `controllability_gramian()` solves `A W + W A^T + B B^T = 0`.
+* `observability_gramian()` solves `A^T W + W A + C^T C = 0`.
+* A discrete system uses the discrete Lyapunov equations, `A W A^T - W + B B^T = 0` and
+  `A^T W A - W + C^T C = 0`.
+* `lyapunov_p()` solves `A^T P + P A = -Q` for a stability certificate.
+
+The Lyapunov equation is solved as a linear system of `NX^2` unknowns with the Kronecker product,
+which is simple and accurate for the small systems this crate is intended for. The linear system
+is dynamically sized, so this module requires the `std` feature.
+*/
+
+use na::{DMatrix, DVector, RealField, SMatrix};
+use nalgebra as na;
+use num_traits::NumCast;
+
+use crate::{StateSpace, StateSpaceError, SystemTime};
+
+// Solves the Lyapunov equation A W + W A^T + Q = 0, or A W A^T - W + Q = 0 for a discrete
+// system. With column major vec(), vec(A W) = (I x A) vec(W), vec(W A^T) = (A x I) vec(W), and
+// vec(A W A^T) = (A x A) vec(W). Returns None if the Kronecker sum is singular.
+pub(crate) fn lyapunov<T: RealField, const N: usize>(
+    a: &SMatrix<T, N, N>,
+    q: &SMatrix<T, N, N>,
+    time: SystemTime,
+) -> Option<SMatrix<T, N, N>> {
+    let a = DMatrix::from_iterator(N, N, a.iter().cloned());
+    let eye = DMatrix::<T>::identity(N, N);
+    let kron = match time {
+        SystemTime::Continuous => eye.kronecker(&a) + a.kronecker(&eye),
+        SystemTime::Discrete => a.kronecker(&a) - DMatrix::identity(N * N, N * N),
+    };
+    let rhs = DVector::from_iterator(N * N, q.iter().map(|v| -v.clone()));
+    let w = kron.lu().solve(&rhs)?;
+
+    // The exact solution is symmetric, remove the round off which is not.
+    let w: SMatrix<T, N, N> = SMatrix::from_iterator(w.iter().cloned());
+    let half = T::from_f64(0.5).expect("Conversion failed");
+    Some((w.clone() + w.transpose()) * half)
+}
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: RealField + NumCast,
+{
+    /// The controllability Gramian `W`, the solution of `A W + W A^T + B B^T = 0`. For a
+    /// discrete system it is the solution of `A W A^T - W + B B^T = 0`.
+    ///
+    /// Returns `StateSpaceError::NotStable` if the system is not stable, see `is_stable()`, since
+    /// the Gramian is then unbounded.
+    ///
+    /// The first order system `3 / (s + 2)`, with `A = -2` and `B = 3`, has the Gramian
+    /// `B^2 / (2 * 2) = 9 / 4`.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, StateSpaceError};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![-2.0]).set_b(matrix![3.0]).set_c(matrix![1.0]);
+    /// let w = sys.controllability_gramian().unwrap();
+    /// assert!((w[(0, 0)] - 9.0 / 4.0).abs() < 1e-12);
+    ///
+    /// // The second order system of Example 2 satisfies the Lyapunov equation.
+    /// let omega = 2.0 * std::f64::consts::PI;
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; -omega * omega, -2.0 * 0.707 * omega])
+    ///     .set_b(matrix![0.0; omega * omega]);
+    /// let w = sys.controllability_gramian().unwrap();
+    /// let a = sys.get_a();
+    /// let b = sys.get_b();
+    /// let residual = a * w + w * a.transpose() + b * b.transpose();
+    /// assert!(residual.norm() < 1e-9 * w.norm());
+    ///
+    /// // An integrator is not stable.
+    /// sys.set_a(matrix![0.0, 1.0; 0.0, -1.0]);
+    /// assert_eq!(Err(StateSpaceError::NotStable), sys.controllability_gramian());
+    /// ```
+    pub fn controllability_gramian(&self) -> Result<SMatrix<T, NX, NX>, StateSpaceError> {
+        if !self.is_stable() {
+            return Err(StateSpaceError::NotStable);
+        }
+        let q = self.b.clone() * self.b.transpose();
+        lyapunov(&self.a, &q, self.time).ok_or(StateSpaceError::SingularMatrix)
+    }
+
+    /// The observability Gramian `W`, the solution of `A^T W + W A + C^T C = 0`. For a discrete
+    /// system it is the solution of `A^T W A - W + C^T C = 0`.
+    ///
+    /// Returns `StateSpaceError::NotStable` if the system is not stable, see `is_stable()`.
+    ///
+    /// The first order system `5 / (s + 2)`, with `A = -2` and `C = 5`, has the Gramian
+    /// `C^2 / (2 * 2) = 25 / 4`. The discrete system `x(n+1) = 0.5 x`, `y = x` has the Gramian
+    /// `1 / (1 - 0.5^2) = 4 / 3`.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![-2.0]).set_b(matrix![1.0]).set_c(matrix![5.0]);
+    /// let w = sys.observability_gramian().unwrap();
+    /// assert!((w[(0, 0)] - 25.0 / 4.0).abs() < 1e-12);
+    ///
+    /// sys.set_a(matrix![0.5]).set_c(matrix![1.0]).set_discrete();
+    /// let w = sys.observability_gramian().unwrap();
+    /// assert!((w[(0, 0)] - 4.0 / 3.0).abs() < 1e-12);
+    /// ```
+    pub fn observability_gramian(&self) -> Result<SMatrix<T, NX, NX>, StateSpaceError> {
+        if !self.is_stable() {
+            return Err(StateSpaceError::NotStable);
+        }
+        let q = self.c.transpose() * self.c.clone();
+        lyapunov(&self.a.transpose(), &q, self.time).ok_or(StateSpaceError::SingularMatrix)
+    }
+
+    /// Solves the Lyapunov equation `A^T P + P A = -Q`, or `A^T P A - P = -Q` for a discrete
+    /// system, and checks that `P` is positive definite. With a positive definite `Q`, a
+    /// positive definite `P` proves that the system is stable, since `V(x) = x^T P x` is then a
+    /// Lyapunov function. `q = None` uses the identity.
+    ///
+    /// Returns an error if:
+    /// * The equation has no unique solution, `StateSpaceError::SingularMatrix`. This happens
+    ///   when two eigenvalues of A sum to zero, for example a pure integrator.
+    /// * The solution is not positive definite, `StateSpaceError::NotPositiveDefinite`, so the
+    ///   stability of the system is not proven. For a positive definite `Q` this means the system
+    ///   is not stable.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, StateSpaceError};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; -2.0, -3.0]);
+    /// let p = sys.lyapunov_p(None).unwrap();
+    /// let residual = sys.get_a().transpose() * p + p * sys.get_a() + matrix![1.0, 0.0; 0.0, 1.0];
+    /// assert!(residual.norm() < 1e-12);
+    /// assert!(p.symmetric_eigenvalues().iter().all(|e| *e > 0.0));
+    ///
+    /// // Poles at +1 and -2.
+    /// sys.set_a(matrix![0.0, 1.0; 2.0, -1.0]);
+    /// assert_eq!(Err(StateSpaceError::NotPositiveDefinite), sys.lyapunov_p(None));
+    /// ```
+    pub fn lyapunov_p(
+        &self,
+        q: Option<SMatrix<T, NX, NX>>,
+    ) -> Result<SMatrix<T, NX, NX>, StateSpaceError> {
+        let q = q.unwrap_or_else(SMatrix::identity);
+        let p =
+            lyapunov(&self.a.transpose(), &q, self.time).ok_or(StateSpaceError::SingularMatrix)?;
+        DMatrix::from_iterator(NX, NX, p.iter().cloned())
+            .cholesky()
+            .ok_or(StateSpaceError::NotPositiveDefinite)?;
+        Ok(p)
+    }
+}
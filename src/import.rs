@@ -0,0 +1,177 @@
+/*!
+# import
+The `import` module reads a `StateSpace` system from a sectioned text format, so matrices
+exported from MATLAB or a similar design tool are loaded without copying numbers by hand.
+
+## Features.
+* `from_reader()` reads from any `std::io::Read`, for example a file or a string.
+* The dimensions of each matrix are checked against the const generics of the system.
+
+## Format.
+Each matrix follows a header line, `[A]`, `[B]`, `[C]`, or `[D]`, with one row per line. The
+elements of a row are separated by commas or whitespace, so a CSV block exported with MATLAB's
+`writematrix()` can be pasted below its header. An optional `[dt]` section holds the time step.
+Blank lines and text after a `#` are ignored.
+
+```text
+# Example 1, a first order lag.
+[A]
+-1.0
+[B]
+1.0
+[C]
+1.0
+[D]
+0.0
+[dt]
+0.1
+```
+
+The sections can be in any order. `[A]`, `[B]`, and `[C]` are required, `D` is zeros without a
+`[D]` section, and `dt` keeps its default without a `[dt]` section. The reader uses `std::io`,
+so this module requires the `std` feature.
+*/
+
+use std::io::{BufRead, BufReader, Read};
+
+use na::SMatrix;
+use nalgebra as na;
+use num_traits::{NumCast, One, Zero};
+
+use crate::{StateSpace, StateSpaceError};
+
+// The section headers, in the order of the parsed data.
+const SECTIONS: [&str; 5] = ["A", "B", "C", "D", "dt"];
+
+// The rows of one section.
+type Section<T> = Vec<Vec<T>>;
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    /// Reads a system in the sectioned text format of the module documentation.
+    ///
+    /// Returns an error if:
+    /// * A line can not be read or parsed, `StateSpaceError::Parse` with the line number. This
+    ///   covers an unknown or repeated section, a row before the first header, and an element
+    ///   which is not a number.
+    /// * A number does not fit in `T`, `StateSpaceError::ConversionFailed`.
+    /// * `[A]`, `[B]`, or `[C]` is missing, `StateSpaceError::MissingField`.
+    /// * A matrix does not have the size of the const generics, or `[dt]` is not a single
+    ///   value, `StateSpaceError::DimensionMismatch`.
+    /// * `dt` is not positive, `StateSpaceError::NonPositiveDt`.
+    ///
+    /// This example reads the system of Example 1.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, StateSpaceError};
+    ///
+    /// let text = "
+    ///     [A]
+    ///     -1.0 # A first order lag.
+    ///     [B]
+    ///     1.0
+    ///     [C]
+    ///     1.0
+    ///     [D]
+    ///     0.0
+    ///     [dt]
+    ///     0.1
+    /// ";
+    /// let sys: StateSpace<f64, 1, 1, 1> = StateSpace::from_reader(text.as_bytes()).unwrap();
+    /// assert_eq!(matrix![-1.0], sys.get_a());
+    /// assert_eq!(matrix![1.0], sys.get_b());
+    /// assert_eq!(matrix![1.0], sys.get_c());
+    /// assert_eq!(matrix![0.0], sys.get_d());
+    /// assert_eq!(0.1, sys.get_dt());
+    ///
+    /// // A CSV block for a second order system, without D and dt.
+    /// let text = "[A]\n0, 1\n-4, -2\n[B]\n0\n4\n[C]\n1, 0\n";
+    /// let sys: StateSpace<f64, 1, 2, 1> = StateSpace::from_reader(text.as_bytes()).unwrap();
+    /// assert_eq!(matrix![0.0, 1.0; -4.0, -2.0], sys.get_a());
+    /// assert_eq!(matrix![0.0], sys.get_d());
+    ///
+    /// // The same text for the wrong number of states, a missing section, and a typo.
+    /// let result = StateSpace::<f64, 1, 3, 1>::from_reader(text.as_bytes());
+    /// assert_eq!(Some(StateSpaceError::DimensionMismatch), result.err());
+    /// let result = StateSpace::<f64, 1, 2, 1>::from_reader("[A]\n0, 1\n-4, -2\n".as_bytes());
+    /// assert_eq!(Some(StateSpaceError::MissingField("B")), result.err());
+    /// let result = StateSpace::<f64, 1, 1, 1>::from_reader("[A]\n-1.0\n[B]\n1.O\n".as_bytes());
+    /// assert_eq!(Some(StateSpaceError::Parse(4)), result.err());
+    /// ```
+    pub fn from_reader<R: Read>(r: R) -> Result<Self, StateSpaceError> {
+        let mut sections: [Option<Section<T>>; 5] = [None, None, None, None, None];
+        let mut current: Option<usize> = None;
+
+        for (i, line) in BufReader::new(r).lines().enumerate() {
+            let number = i + 1;
+            let line = line.map_err(|_| StateSpaceError::Parse(number))?;
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let index = SECTIONS
+                    .iter()
+                    .position(|s| *s == name.trim())
+                    .ok_or(StateSpaceError::Parse(number))?;
+                if sections[index].is_some() {
+                    return Err(StateSpaceError::Parse(number));
+                }
+                sections[index] = Some(Vec::new());
+                current = Some(index);
+                continue;
+            }
+
+            let index = current.ok_or(StateSpaceError::Parse(number))?;
+            let row = parse_row(line, number)?;
+            if let Some(rows) = sections[index].as_mut() {
+                rows.push(row);
+            }
+        }
+
+        let [a, b, c, d, dt] = sections;
+        let mut sys = Self::new();
+        sys.set_a(to_matrix(a.ok_or(StateSpaceError::MissingField("A"))?)?)
+            .set_b(to_matrix(b.ok_or(StateSpaceError::MissingField("B"))?)?)
+            .set_c(to_matrix(c.ok_or(StateSpaceError::MissingField("C"))?)?);
+        if let Some(d) = d {
+            sys.set_d(to_matrix(d)?);
+        }
+        if let Some(dt) = dt {
+            let dt: SMatrix<T, 1, 1> = to_matrix(dt)?;
+            sys.try_set_dt(dt[0].clone())?;
+        }
+        Ok(sys)
+    }
+}
+
+// Parses the elements of a row, separated by commas or whitespace.
+fn parse_row<T: NumCast>(line: &str, number: usize) -> Result<Vec<T>, StateSpaceError> {
+    line.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            let value: f64 = s.parse().map_err(|_| StateSpaceError::Parse(number))?;
+            T::from(value).ok_or(StateSpaceError::ConversionFailed)
+        })
+        .collect()
+}
+
+// The matrix of a section, which must have R rows of C elements.
+fn to_matrix<T: na::Scalar, const R: usize, const C: usize>(
+    rows: Section<T>,
+) -> Result<SMatrix<T, R, C>, StateSpaceError> {
+    if rows.len() != R || rows.iter().any(|row| row.len() != C) {
+        return Err(StateSpaceError::DimensionMismatch);
+    }
+    Ok(SMatrix::from_fn(|i, j| rows[i][j].clone()))
+}
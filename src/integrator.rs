@@ -0,0 +1,160 @@
+/*!
+# integrator
+The `integrator` module contains the `Integrator` trait, which lets users choose the numerical
+scheme `update()` uses to integrate a continuous `StateSpace` system.
+
+## Features.
+* `ForwardEuler`, the default, is fast and simple.
+* `Rk4`, the classic 4th order Runge-Kutta, is much more accurate for the same `dt`.
+* Other schemes, such as RK2 or an implicit method for stiff systems, are added by implementing
+  `Integrator` for a new type and selecting it with `StateSpace::set_integrator()`.
+
+The integrator is stored as a function pointer, so it does not allocate and a `StateSpace` stays
+`Copy`.
+*/
+
+use na::{SMatrix, SVector};
+use nalgebra as na;
+use num_traits::{NumCast, One, Zero};
+
+// The step function of an Integrator, as stored by StateSpace.
+pub(crate) type StepFn<T, const NU: usize, const NX: usize> = fn(
+    &SMatrix<T, NX, NX>,
+    &SMatrix<T, NX, NU>,
+    &SVector<T, NX>,
+    &SVector<T, NU>,
+    T,
+) -> SVector<T, NX>;
+
+// The step function of a deserialized StateSpace, as serde can not store a function pointer.
+#[cfg(feature = "serde")]
+pub(crate) fn default_step<T, const NU: usize, const NX: usize>() -> StepFn<T, NU, NX>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    <ForwardEuler as Integrator<T, NU, NX>>::step
+}
+
+/// A numerical scheme for the continuous system `xDot = Ax + Bu`.
+///
+/// `step()` returns `x(n+1)`, the state one step of `dt` after `x`, with the input `u` held
+/// constant over the step. Clamping, rate limits, and anti-windup are applied by `update()`, so
+/// an integrator only needs to solve the linear equation.
+///
+/// This example adds backward Euler, `x(n+1) = (I - A dt)^-1 (x + B u dt)`, which is stable for
+/// any `dt` on a stable system. On the stiff system `xDot = -50 x` with `dt = 0.05`, forward
+/// Euler is unstable and RK4 is stable. Backward Euler decays without overshoot, as the exact
+/// solution does.
+///
+/// ```rust
+/// use nalgebra::{SMatrix, SVector};
+/// use state_space::{Integrator, Rk4, StateSpace, SysVec};
+///
+/// struct BackwardEuler;
+///
+/// impl Integrator<f64, 1, 1> for BackwardEuler {
+///     fn step(
+///         a: &SMatrix<f64, 1, 1>,
+///         b: &SMatrix<f64, 1, 1>,
+///         x: &SVector<f64, 1>,
+///         u: &SVector<f64, 1>,
+///         dt: f64,
+///     ) -> SVector<f64, 1> {
+///         let lhs = SMatrix::<f64, 1, 1>::identity() - a * dt;
+///         lhs.try_inverse().unwrap() * (x + b * u * dt)
+///     }
+/// }
+///
+/// let mut euler: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+/// euler
+///     .set_a(SMatrix::from_element(-50.0))
+///     .set_x(SysVec::from_val(1.0))
+///     .set_dt(0.05);
+/// let mut rk4 = euler;
+/// rk4.set_integrator::<Rk4>();
+/// let mut implicit = euler;
+/// implicit.set_integrator::<BackwardEuler>();
+///
+/// for _ in 0..20 {
+///     euler.update();
+///     rk4.update();
+///     implicit.update();
+/// }
+/// assert!(euler.get_x()[0].abs() > 1000.0);
+/// assert!(rk4.get_x()[0].abs() < 1e-3);
+/// assert!(implicit.get_x()[0] > 0.0 && implicit.get_x()[0] < 1e-3);
+/// ```
+pub trait Integrator<T, const NU: usize, const NX: usize> {
+    fn step(
+        a: &SMatrix<T, NX, NX>,
+        b: &SMatrix<T, NX, NU>,
+        x: &SVector<T, NX>,
+        u: &SVector<T, NU>,
+        dt: T,
+    ) -> SVector<T, NX>;
+}
+
+/// Forward Euler, `x(n+1) = x + (Ax + Bu) dt`. This is the default integrator of `update()`.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct ForwardEuler;
+
+impl<T, const NU: usize, const NX: usize> Integrator<T, NU, NX> for ForwardEuler
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + One
+        + Zero
+        + NumCast,
+{
+    fn step(
+        a: &SMatrix<T, NX, NX>,
+        b: &SMatrix<T, NX, NU>,
+        x: &SVector<T, NX>,
+        u: &SVector<T, NU>,
+        dt: T,
+    ) -> SVector<T, NX> {
+        let x_dot = a * x + b * u;
+        x + x_dot * dt
+    }
+}
+
+/// The classic 4th order Runge-Kutta. The input is held constant over the step, so all four
+/// stages share the same `Bu` term. This is the integrator of `update_rk4()`.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Rk4;
+
+impl<T, const NU: usize, const NX: usize> Integrator<T, NU, NX> for Rk4
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + One
+        + Zero
+        + NumCast,
+{
+    fn step(
+        a: &SMatrix<T, NX, NX>,
+        b: &SMatrix<T, NX, NU>,
+        x: &SVector<T, NX>,
+        u: &SVector<T, NU>,
+        dt: T,
+    ) -> SVector<T, NX> {
+        let half_dt = dt.clone() * T::from(0.5).expect("Conversion failed");
+        let two = T::from(2.0).expect("Conversion failed");
+        let sixth_dt = dt.clone() * T::from(1.0 / 6.0).expect("Conversion failed");
+
+        let bu = b * u;
+        let k1 = a * x + &bu;
+        let k2 = a * (x + &k1 * half_dt.clone()) + &bu;
+        let k3 = a * (x + &k2 * half_dt) + &bu;
+        let k4 = a * (x + &k3 * dt) + bu;
+        x + (k1 + (k2 + k3) * two + k4) * sixth_dt
+    }
+}
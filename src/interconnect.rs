@@ -0,0 +1,348 @@
+/*!
+# interconnect
+The `interconnect` module contains functions which combine two `StateSpace` systems into a single
+system, so a plant and a controller do not need to be assembled from block matrices by hand.
+
+## Features.
+* `series()` connects the output of one system to the input of another.
+* `parallel()` feeds the same input to two systems and sums their outputs.
+* `feedback()` closes a loop around a plant with a controller in the feedback path.
+
+The state dimension of the combined system is a separate const generic, which must equal the sum
+of the state dimensions of the two systems. It is usually given by annotating the result, for
+example `let sys: StateSpace<f64, 1, 2, 1> = series(&sys1, &sys2)?;`.
+*/
+
+use na::{RealField, SMatrix};
+use nalgebra as na;
+use num_traits::{NumCast, One, Zero};
+
+use crate::{StateSpace, StateSpaceError};
+
+// Checks that two systems can be combined, they must share dt and continuous/discrete time, and
+// the combined state dimension must be the sum of the two.
+fn check_compatible<
+    T,
+    const NU1: usize,
+    const NX1: usize,
+    const NY1: usize,
+    const NU2: usize,
+    const NX2: usize,
+    const NY2: usize,
+>(
+    sys1: &StateSpace<T, NU1, NX1, NY1>,
+    sys2: &StateSpace<T, NU2, NX2, NY2>,
+    nx: usize,
+) -> Result<(), StateSpaceError>
+where
+    T: nalgebra::Scalar,
+{
+    if nx != NX1 + NX2 {
+        return Err(StateSpaceError::DimensionMismatch);
+    }
+    if sys1.dt != sys2.dt {
+        return Err(StateSpaceError::DtMismatch);
+    }
+    if sys1.time != sys2.time {
+        return Err(StateSpaceError::SystemTimeMismatch);
+    }
+    Ok(())
+}
+
+/// Connects two systems in series (cascade), the output of `sys1` is the input of `sys2`.
+///
+/// ```text
+/// u --> [sys1] --> [sys2] --> y
+/// ```
+///
+/// The combined state is `[x1; x2]`, so the A matrix is block lower-triangular:
+///
+/// ```text
+/// A = [ A1     0  ]    B = [ B1    ]
+///     [ B2*C1  A2 ]        [ B2*D1 ]
+///
+/// C = [ D2*C1  C2 ]    D = D2*D1
+/// ```
+///
+/// Errors:
+/// * `DimensionMismatch` if `NX != NX1 + NX2`.
+/// * `DtMismatch` if the systems have a different `dt`.
+/// * `SystemTimeMismatch` if one system is continuous and the other is discrete.
+///
+/// This example cascades two first order lags and compares the result with simulating them one
+/// after the other.
+///
+/// ```rust
+/// use nalgebra::matrix;
+/// use state_space::{series, StateSpace, SysVec};
+///
+/// let mut sys1: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+/// sys1.set_a(matrix![-1.0]).set_b(matrix![1.0]).set_c(matrix![1.0]).set_dt(0.1);
+/// let mut sys2: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+/// sys2.set_a(matrix![-2.0]).set_b(matrix![2.0]).set_c(matrix![1.0]).set_dt(0.1);
+///
+/// let mut sys: StateSpace<f64, 1, 2, 1> = series(&sys1, &sys2).unwrap();
+///
+/// sys.set_u(SysVec::from_val(1.0));
+/// sys1.set_u(SysVec::from_val(1.0));
+/// for _ in 0..50 {
+///     sys.update();
+///     sys1.update();
+///     sys2.set_u(*SysVec::new().set_val(sys1.get_y()));
+///     sys2.update();
+///     assert!((sys.get_y() - sys2.get_y()).abs().max() < 1e-12);
+/// }
+/// ```
+pub fn series<
+    T,
+    const NU: usize,
+    const NX1: usize,
+    const NM: usize,
+    const NX2: usize,
+    const NY: usize,
+    const NX: usize,
+>(
+    sys1: &StateSpace<T, NU, NX1, NM>,
+    sys2: &StateSpace<T, NM, NX2, NY>,
+) -> Result<StateSpace<T, NU, NX, NY>, StateSpaceError>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    check_compatible(sys1, sys2, NX)?;
+
+    let mut a: SMatrix<T, NX, NX> = SMatrix::zeros();
+    a.fixed_view_mut::<NX1, NX1>(0, 0).copy_from(&sys1.a);
+    a.fixed_view_mut::<NX2, NX1>(NX1, 0)
+        .copy_from(&(sys2.b.clone() * sys1.c.clone()));
+    a.fixed_view_mut::<NX2, NX2>(NX1, NX1).copy_from(&sys2.a);
+
+    let mut b: SMatrix<T, NX, NU> = SMatrix::zeros();
+    b.fixed_view_mut::<NX1, NU>(0, 0).copy_from(&sys1.b);
+    b.fixed_view_mut::<NX2, NU>(NX1, 0)
+        .copy_from(&(sys2.b.clone() * sys1.d.clone()));
+
+    let mut c: SMatrix<T, NY, NX> = SMatrix::zeros();
+    c.fixed_view_mut::<NY, NX1>(0, 0)
+        .copy_from(&(sys2.d.clone() * sys1.c.clone()));
+    c.fixed_view_mut::<NY, NX2>(0, NX1).copy_from(&sys2.c);
+
+    let mut sys = StateSpace::new();
+    sys.set_a(a)
+        .set_b(b)
+        .set_c(c)
+        .set_d(sys2.d.clone() * sys1.d.clone())
+        .set_dt(sys1.dt.clone());
+    sys.time = sys1.time;
+    Ok(sys)
+}
+
+/// Connects two systems in parallel, both systems receive the same input `u` and their outputs are
+/// summed, `y = y1 + y2`. The systems must have the same `NU` and `NY`, which is enforced by the
+/// types.
+///
+/// ```text
+///      +--> [sys1] --+
+/// u ---|             (+)--> y
+///      +--> [sys2] --+
+/// ```
+///
+/// The combined state is `[x1; x2]`:
+///
+/// ```text
+/// A = [ A1  0  ]    B = [ B1 ]
+///     [ 0   A2 ]        [ B2 ]
+///
+/// C = [ C1  C2 ]    D = D1 + D2
+/// ```
+///
+/// Errors are the same as for `series()`.
+///
+/// The DC gain of two parallel lags is the sum of the individual DC gains, 2 + 3 in this example.
+///
+/// ```rust
+/// use nalgebra::matrix;
+/// use state_space::{parallel, StateSpace, SysVec};
+///
+/// let mut sys1: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+/// sys1.set_a(matrix![-1.0]).set_b(matrix![2.0]).set_c(matrix![1.0]).set_dt(0.05);
+/// let mut sys2: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+/// sys2.set_a(matrix![-4.0]).set_b(matrix![4.0]).set_c(matrix![3.0]).set_dt(0.05);
+///
+/// let mut sys: StateSpace<f64, 1, 2, 1> = parallel(&sys1, &sys2).unwrap();
+/// sys.set_u(SysVec::from_val(1.0));
+/// for _ in 0..1000 {
+///     sys.update();
+/// }
+/// assert!((sys.get_y()[0] - 5.0).abs() < 1e-9);
+/// ```
+pub fn parallel<
+    T,
+    const NU: usize,
+    const NX1: usize,
+    const NX2: usize,
+    const NY: usize,
+    const NX: usize,
+>(
+    sys1: &StateSpace<T, NU, NX1, NY>,
+    sys2: &StateSpace<T, NU, NX2, NY>,
+) -> Result<StateSpace<T, NU, NX, NY>, StateSpaceError>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    check_compatible(sys1, sys2, NX)?;
+
+    let mut a: SMatrix<T, NX, NX> = SMatrix::zeros();
+    a.fixed_view_mut::<NX1, NX1>(0, 0).copy_from(&sys1.a);
+    a.fixed_view_mut::<NX2, NX2>(NX1, NX1).copy_from(&sys2.a);
+
+    let mut b: SMatrix<T, NX, NU> = SMatrix::zeros();
+    b.fixed_view_mut::<NX1, NU>(0, 0).copy_from(&sys1.b);
+    b.fixed_view_mut::<NX2, NU>(NX1, 0).copy_from(&sys2.b);
+
+    let mut c: SMatrix<T, NY, NX> = SMatrix::zeros();
+    c.fixed_view_mut::<NY, NX1>(0, 0).copy_from(&sys1.c);
+    c.fixed_view_mut::<NY, NX2>(0, NX1).copy_from(&sys2.c);
+
+    let mut sys = StateSpace::new();
+    sys.set_a(a)
+        .set_b(b)
+        .set_c(c)
+        .set_d(sys1.d.clone() + sys2.d.clone())
+        .set_dt(sys1.dt.clone());
+    sys.time = sys1.time;
+    Ok(sys)
+}
+
+/// The sign of the feedback path used by `feedback()`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FeedbackSign {
+    /// The controller output is subtracted from the reference, `u = r - yc`.
+    Negative,
+    /// The controller output is added to the reference, `u = r + yc`.
+    Positive,
+}
+
+/// Closes a feedback loop around `plant` with `controller` in the feedback path. The input of
+/// the combined system is the reference `r` and the output is the plant output `y`.
+///
+/// ```text
+/// r --> (+/-) --u--> [plant] ---+--> y
+///         ^                     |
+///         +--yc-- [controller] <+
+/// ```
+///
+/// With `s = -1` for negative feedback and `s = +1` for positive feedback, the loop equations are
+/// `u = r + s*yc`, `yc = Cc*xc + Dc*y`, and `y = Cp*xp + Dp*u`. When both D matrices are
+/// nonzero there is an algebraic loop, which is solved with `E = (I - s*Dp*Dc)^{-1}`, i.e.
+/// `(I + Dp*Dc)^{-1}` for negative feedback. The combined state is `[xp; xc]`:
+///
+/// ```text
+/// A = [ Ap + s*Bp*Dc*E*Cp   Bp*(s*Cc + Dc*E*Dp*Cc) ]    B = [ Bp*(I + s*Dc*E*Dp) ]
+///     [ Bc*E*Cp             Ac + s*Bc*E*Dp*Cc      ]        [ Bc*E*Dp            ]
+///
+/// C = [ E*Cp   s*E*Dp*Cc ]    D = E*Dp
+/// ```
+///
+/// Errors are the same as for `series()`, plus `SingularMatrix` when `I - s*Dp*Dc` can not be
+/// inverted.
+///
+/// A proportional controller `k = 3` around the plant `1/(s + 1)` moves the pole to `-1 - k`.
+/// The controller has no states, so it is a pure D matrix.
+///
+/// ```rust
+/// use nalgebra::matrix;
+/// use state_space::{feedback, FeedbackSign, StateSpace, StateSpaceError};
+///
+/// let mut plant: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+/// plant.set_a(matrix![-1.0]).set_b(matrix![1.0]).set_c(matrix![1.0]);
+/// let mut gain: StateSpace<f64, 1, 0, 1> = StateSpace::new();
+/// gain.set_d(matrix![3.0]);
+///
+/// let sys: StateSpace<f64, 1, 1, 1> = feedback(&plant, &gain, FeedbackSign::Negative).unwrap();
+/// assert_eq!(matrix![-4.0], sys.get_a());
+///
+/// // An algebraic loop, G = (s + 2)/(s + 1) with unity feedback has a DC gain of 2/3.
+/// plant.set_d(matrix![1.0]);
+/// gain.set_d(matrix![1.0]);
+/// let sys: StateSpace<f64, 1, 1, 1> = feedback(&plant, &gain, FeedbackSign::Negative).unwrap();
+/// let dc = sys.get_d() - sys.get_c() * sys.get_a().try_inverse().unwrap() * sys.get_b();
+/// assert!((dc[0] - 2.0 / 3.0).abs() < 1e-12);
+///
+/// // With unity feedthrough in both paths, positive feedback has no solution.
+/// let result: Result<StateSpace<f64, 1, 1, 1>, _> =
+///     feedback(&plant, &gain, FeedbackSign::Positive);
+/// assert_eq!(Some(StateSpaceError::SingularMatrix), result.err());
+/// ```
+pub fn feedback<
+    T,
+    const NU: usize,
+    const NXP: usize,
+    const NY: usize,
+    const NXC: usize,
+    const NX: usize,
+>(
+    plant: &StateSpace<T, NU, NXP, NY>,
+    controller: &StateSpace<T, NY, NXC, NU>,
+    sign: FeedbackSign,
+) -> Result<StateSpace<T, NU, NX, NY>, StateSpaceError>
+where
+    T: RealField + NumCast,
+{
+    check_compatible(plant, controller, NX)?;
+
+    let s = match sign {
+        FeedbackSign::Negative => -T::one(),
+        FeedbackSign::Positive => T::one(),
+    };
+    let (ap, bp, cp, dp) = (&plant.a, &plant.b, &plant.c, &plant.d);
+    let (ac, bc, cc, dc) = (&controller.a, &controller.b, &controller.c, &controller.d);
+
+    // Solve the algebraic loop.
+    let e = (SMatrix::<T, NY, NY>::identity() - dp * dc * s.clone())
+        .try_inverse()
+        .ok_or(StateSpaceError::SingularMatrix)?;
+    let e_cp = &e * cp;
+    let e_dp = &e * dp;
+    let e_dp_cc = &e_dp * cc;
+
+    let mut a: SMatrix<T, NX, NX> = SMatrix::zeros();
+    a.fixed_view_mut::<NXP, NXP>(0, 0)
+        .copy_from(&(ap + bp * dc * &e_cp * s.clone()));
+    a.fixed_view_mut::<NXP, NXC>(0, NXP)
+        .copy_from(&(bp * (cc * s.clone() + dc * &e_dp_cc)));
+    a.fixed_view_mut::<NXC, NXP>(NXP, 0)
+        .copy_from(&(bc * &e_cp));
+    a.fixed_view_mut::<NXC, NXC>(NXP, NXP)
+        .copy_from(&(ac + bc * &e_dp_cc * s.clone()));
+
+    let mut b: SMatrix<T, NX, NU> = SMatrix::zeros();
+    b.fixed_view_mut::<NXP, NU>(0, 0)
+        .copy_from(&(bp * (SMatrix::<T, NU, NU>::identity() + dc * &e_dp * s.clone())));
+    b.fixed_view_mut::<NXC, NU>(NXP, 0).copy_from(&(bc * &e_dp));
+
+    let mut c: SMatrix<T, NY, NX> = SMatrix::zeros();
+    c.fixed_view_mut::<NY, NXP>(0, 0).copy_from(&e_cp);
+    c.fixed_view_mut::<NY, NXC>(0, NXP)
+        .copy_from(&(e_dp_cc * s));
+
+    let mut sys = StateSpace::new();
+    sys.set_a(a)
+        .set_b(b)
+        .set_c(c)
+        .set_d(e_dp)
+        .set_dt(plant.dt.clone());
+    sys.time = plant.time;
+    Ok(sys)
+}
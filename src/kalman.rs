@@ -0,0 +1,178 @@
+/*!
+# kalman
+The `kalman` module contains `KalmanFilter`, a discrete time Kalman filter which estimates the
+state of a `StateSpace` plant from noisy measurements of its output.
+
+## Features.
+* Uses the `A`, `B`, `C`, and `D` matrices of the plant, a continuous plant is discretized with
+  `to_discrete()` first.
+* `predict()` propagates the estimate and its covariance `P` through the plant dynamics.
+* `update()` corrects the estimate with a measurement of `y`.
+*/
+
+use na::{RealField, SMatrix, SVector};
+use nalgebra as na;
+use num_traits::NumCast;
+
+use crate::validate::symmetry_tol;
+use crate::{is_positive_definite, is_symmetric, StateSpace, StateSpaceError, SysVec};
+
+/// Kalman filter for the plant `x(n+1) = Ax + Bu + w`, `y = Cx + Du + v`, where the process
+/// noise `w` has covariance `Q` and the measurement noise `v` has covariance `R`.
+///
+/// This example estimates a constant of 5.0 from measurements with uniform noise in the range
+/// +/-0.5. A simple linear congruential generator provides repeatable noise.
+///
+/// ```rust
+/// use nalgebra::{matrix, vector};
+/// use state_space::{KalmanFilter, StateSpace};
+///
+/// let mut plant: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+/// plant
+///     .set_a(matrix![1.0])
+///     .set_c(matrix![1.0])
+///     .set_dt(1.0)
+///     .set_discrete();
+///
+/// let mut kf = KalmanFilter::new(plant, matrix![1e-6], matrix![1.0 / 12.0]);
+///
+/// let mut seed: u64 = 1;
+/// let mut noise = || {
+///     seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+///     (seed >> 11) as f64 / (1u64 << 53) as f64 - 0.5
+/// };
+///
+/// for _ in 0..200 {
+///     kf.predict();
+///     kf.update(vector![5.0 + noise()]).unwrap();
+/// }
+/// assert!((kf.get_estimate().get_val()[0] - 5.0).abs() < 0.1);
+/// assert!(kf.get_covariance()[(0, 0)] < 0.01);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct KalmanFilter<T, const NU: usize, const NX: usize, const NY: usize> {
+    sys: StateSpace<T, NU, NX, NY>,
+    q: SMatrix<T, NX, NX>,
+    r: SMatrix<T, NY, NY>,
+    x_hat: SysVec<T, NX>,
+    p: SMatrix<T, NX, NX>,
+}
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> KalmanFilter<T, NU, NX, NY>
+where
+    T: RealField + NumCast,
+{
+    /// Create a filter for `sys` with process noise covariance `q` and measurement noise
+    /// covariance `r`. A continuous `sys` is discretized with `to_discrete()` at its `dt`, which
+    /// panics if an entry of A is not finite.
+    ///
+    /// The initial estimate is the state `x` of `sys`, and the initial covariance `P` is the
+    /// identity. Either can be changed with `set_estimate()` and `set_covariance()`. The
+    /// covariances are not checked, see `try_new()`.
+    pub fn new(
+        sys: StateSpace<T, NU, NX, NY>,
+        q: SMatrix<T, NX, NX>,
+        r: SMatrix<T, NY, NY>,
+    ) -> Self {
+        let sys = sys.to_discrete();
+        let x_hat = sys.x.clone();
+        Self {
+            sys,
+            q,
+            r,
+            x_hat,
+            p: SMatrix::identity(),
+        }
+    }
+
+    /// Same as `new()`, and checks the covariances first. Returns an error if:
+    /// * `q` or `r` is not symmetric, `StateSpaceError::NotSymmetric`, see `is_symmetric()`.
+    /// * `r` is not positive definite, `StateSpaceError::NotPositiveDefinite`. A `q` of zeros
+    ///   is allowed, for a plant without process noise.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{KalmanFilter, StateSpace, StateSpaceError};
+    ///
+    /// let mut plant: StateSpace<f64, 1, 2, 2> = StateSpace::new();
+    /// plant.set_a(matrix![1.0, 0.1; 0.0, 1.0]).set_c(matrix![1.0, 0.0; 0.0, 1.0]);
+    /// let q = matrix![1e-4, 0.0; 0.0, 1e-4];
+    ///
+    /// assert!(KalmanFilter::try_new(plant, q, matrix![0.1, 0.01; 0.01, 0.1]).is_ok());
+    ///
+    /// let err = KalmanFilter::try_new(plant, q, matrix![0.1, 0.01; 0.0, 0.1]).err();
+    /// assert_eq!(Some(StateSpaceError::NotSymmetric), err);
+    /// let err = KalmanFilter::try_new(plant, q, matrix![0.1, 0.2; 0.2, 0.1]).err();
+    /// assert_eq!(Some(StateSpaceError::NotPositiveDefinite), err);
+    /// ```
+    pub fn try_new(
+        sys: StateSpace<T, NU, NX, NY>,
+        q: SMatrix<T, NX, NX>,
+        r: SMatrix<T, NY, NY>,
+    ) -> Result<Self, StateSpaceError> {
+        if !is_symmetric(&q, symmetry_tol(&q)) || !is_symmetric(&r, symmetry_tol(&r)) {
+            return Err(StateSpaceError::NotSymmetric);
+        }
+        if !is_positive_definite(&r) {
+            return Err(StateSpaceError::NotPositiveDefinite);
+        }
+        Ok(Self::new(sys, q, r))
+    }
+
+    // Setters can be chained in the same way as StateSpace.
+    pub fn set_estimate(&mut self, x_hat: SysVec<T, NX>) -> &mut Self {
+        self.x_hat = x_hat;
+        self
+    }
+
+    pub fn set_covariance(&mut self, p: SMatrix<T, NX, NX>) -> &mut Self {
+        self.p = p;
+        self
+    }
+
+    /// Sets the input `u` which was applied to the plant, used by `predict()` and `update()`.
+    pub fn set_u(&mut self, u: SysVec<T, NU>) -> &mut Self {
+        self.sys.set_u(u);
+        self
+    }
+
+    // Getters return a copy, the same as StateSpace.
+    pub fn get_estimate(&self) -> SysVec<T, NX> {
+        self.x_hat.clone()
+    }
+
+    pub fn get_covariance(&self) -> SMatrix<T, NX, NX> {
+        self.p.clone()
+    }
+
+    /// Time update, `x = Ax + Bu` and `P = A P A^T + Q`.
+    pub fn predict(&mut self) -> &mut Self {
+        let a = &self.sys.a;
+        let x1 = a * self.x_hat.val_ref() + &self.sys.b * self.sys.u.val_ref();
+        self.x_hat.update(x1);
+        self.p = a * &self.p * a.transpose() + &self.q;
+        self
+    }
+
+    /// Measurement update with the measured output `y`. With the innovation covariance
+    /// `S = C P C^T + R` and gain `K = P C^T S^{-1}`:
+    /// * `x = x + K (y - Cx - Du)`
+    /// * `P = (I - K C) P`
+    ///
+    /// Returns `StateSpaceError::SingularMatrix` if `S` can not be inverted, in which case the
+    /// estimate is not changed.
+    pub fn update(&mut self, y: SVector<T, NY>) -> Result<&mut Self, StateSpaceError> {
+        let c = &self.sys.c;
+        let p_ct = &self.p * c.transpose();
+        let s_inv = (c * &p_ct + &self.r)
+            .try_inverse()
+            .ok_or(StateSpaceError::SingularMatrix)?;
+        let k = p_ct * s_inv;
+
+        let y_hat = c * self.x_hat.val_ref() + &self.sys.d * self.sys.u.val_ref();
+        let x1 = self.x_hat.val_ref() + &k * (y - y_hat);
+        self.x_hat.update(x1);
+        self.p = (SMatrix::<T, NX, NX>::identity() - k * c) * &self.p;
+        Ok(self)
+    }
+}
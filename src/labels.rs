@@ -0,0 +1,89 @@
+/*!
+# labels
+The `labels` module contains optional names for the input, state, and output channels of a
+`StateSpace` system, to keep track of which channel is which in a MIMO system.
+
+## Features.
+* `set_input_names()`, `set_state_names()`, and `set_output_names()` check the number of names.
+* The names are `&'static str` so a `StateSpace` stays `Copy` and does not allocate. They have no
+  effect on `update()`.
+*/
+
+use num_traits::{NumCast, One, Zero};
+
+use crate::{StateSpace, StateSpaceError};
+
+// Copies a slice of names into an array of length N, or DimensionMismatch for another length.
+fn to_names<const N: usize>(names: &[&'static str]) -> Result<[&'static str; N], StateSpaceError> {
+    names
+        .try_into()
+        .map_err(|_| StateSpaceError::DimensionMismatch)
+}
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    /// Names the inputs. Returns `StateSpaceError::DimensionMismatch` unless there are exactly
+    /// NU names, in which case the names are not changed.
+    ///
+    /// ```rust
+    /// use state_space::{StateSpace, StateSpaceError};
+    ///
+    /// let mut sys: StateSpace<f64, 2, 1, 1> = StateSpace::new();
+    /// assert_eq!(None, sys.get_input_names());
+    ///
+    /// sys.set_input_names(&["aileron", "elevator"]).unwrap();
+    /// assert_eq!(Some(["aileron", "elevator"]), sys.get_input_names());
+    ///
+    /// let err = sys.set_input_names(&["rudder"]).err();
+    /// assert_eq!(Some(StateSpaceError::DimensionMismatch), err);
+    /// assert_eq!(Some(["aileron", "elevator"]), sys.get_input_names());
+    /// ```
+    pub fn set_input_names(
+        &mut self,
+        names: &[&'static str],
+    ) -> Result<&mut Self, StateSpaceError> {
+        self.input_names = Some(to_names(names)?);
+        Ok(self)
+    }
+
+    /// Names the states. Returns `StateSpaceError::DimensionMismatch` unless there are exactly
+    /// NX names.
+    pub fn set_state_names(
+        &mut self,
+        names: &[&'static str],
+    ) -> Result<&mut Self, StateSpaceError> {
+        self.state_names = Some(to_names(names)?);
+        Ok(self)
+    }
+
+    /// Names the outputs. Returns `StateSpaceError::DimensionMismatch` unless there are exactly
+    /// NY names.
+    pub fn set_output_names(
+        &mut self,
+        names: &[&'static str],
+    ) -> Result<&mut Self, StateSpaceError> {
+        self.output_names = Some(to_names(names)?);
+        Ok(self)
+    }
+
+    // Getters return None when the names were never set.
+    pub fn get_input_names(&self) -> Option<[&'static str; NU]> {
+        self.input_names
+    }
+
+    pub fn get_state_names(&self) -> Option<[&'static str; NX]> {
+        self.state_names
+    }
+
+    pub fn get_output_names(&self) -> Option<[&'static str; NY]> {
+        self.output_names
+    }
+}
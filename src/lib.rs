@@ -10,14 +10,175 @@ results here as a state-space system for use in embedded code.
 * **StateSpace** structure holds:
     * The `A`, `B`, `C`, `D` matrices.
     * Contains vectors of `u`, `x`, and `y` including upper and lower bounds.
-    * Contains time step, `dt`.
-* Provides an `update()` method to step forward in time.
+    * Contains time step, `dt`, read with `get_dt()`. `set_dt_secs()` sets it from a `Duration`.
+    * `num_inputs()`, `num_states()`, and `num_outputs()` return the dimensions.
+* `from_matrices()` creates a system from its matrices and `dt` in one call.
+* **StateSpaceBuilder** checks that a system is complete before it is created.
+* Provides an `update()` method to step forward in time, and `update_n()` to take many steps.
+  `update_out()` returns the output of the step, and `step()` takes one input sample and returns
+  one output sample. `update_with()` takes an input for one step without storing it.
+* Provides an `update_rk4()` method, a more accurate 4th order Runge-Kutta alternative.
+* Provides `update_backward()` to step a continuous system backward in time.
+* Provides `warm_start()` to start from the equilibrium for a constant input, without a transient.
+* Provides `update_with_error()`, which estimates the local truncation error to help choose `dt`.
+  `state_derivative()` returns `Ax + Bu` without stepping, to debug a diverging system.
+* The **Integrator** trait, `set_integrator()`, swaps the scheme `update()` integrates with.
+* `update_raw()` takes a raw `Ax + Bu` step, which also works for a complex scalar type.
+* Provides `try_set_a()` and similar to reject a matrix with a NaN or infinite element.
+* Provides `is_symmetric()` and `is_positive_definite()` to check the weights and covariances of
+  `lqr()` and **KalmanFilter**.
+* Provides `approx_eq()` to compare two systems within a tolerance.
+* Implements `Display` to print a system with aligned matrices, for debugging.
+* Optional names for the inputs, states, and outputs, `set_input_names()` and similar.
+* Optional anti-windup, `set_anti_windup()`, for states behind a saturated output.
+* Optional state feedback `u = -Kx + Nr`, `set_feedback_gain()` and `set_reference_gain()`, to
+  simulate a closed loop.
+* The output is clamped to its bounds, `set_clamp_output(false)` reports it unclamped.
+  `set_clamp_input()` and `set_clamp_state()` turn the clamps of `u` and `x` on or off.
+* Optional scale of each output, `set_output_scale()`, to report `y` in engineering units.
+* Optional output decimation, `set_output_decimation()`, to update `y` every k-th step only.
+* `set_frozen()` holds the state during a mode transition, while `y` still follows `u`.
+* `last_saturation()` reports which inputs and states are at a bound after a step.
+* Optional rate limit on the input, `SysVec::set_rate_limit()`, for actuators with a slew rate.
+* Optional clamp hysteresis, `SysVec::set_clamp_hysteresis()`, so a value at a bound does not
+  chatter on and off it.
+* Provides `to_discrete()` for an exact zero-order-hold discretization, `to_foh()` for a
+  first-order-hold discretization, and `to_tustin()` for the bilinear (trapezoidal)
+  discretization. Discrete systems are stepped with `x(n+1) = Ax + Bu` by `update()`.
+* Provides `eigenvalues()`, `char_poly()`, `is_stable()`, `dc_gain()`, `is_controllable()`,
+  `is_observable()`, and `is_strictly_proper()` to analyze a system. `check_euler_stability()`
+  checks that `dt` is small enough for forward Euler, and `rga()` helps to pair the loops of a
+  MIMO system.
+* Provides `freq_response()` to evaluate `G(jw)` for a Bode diagram.
+* Provides `controllability_gramian()` and `observability_gramian()` for a stable system, and
+  `lyapunov_p()` for a Lyapunov stability certificate.
+* Provides `balance_and_truncate()` to reduce the number of states with balanced truncation, and
+  `minreal()` to remove the uncontrollable and unobservable states.
+* Provides `h2_norm()` and `hinf_norm()` to compare designs.
+* Provides `series()`, `parallel()`, and `feedback()` to combine two systems into one, and the
+  operators `sys2 * sys1` and `sys1 + sys2` as a shorthand for `series()` and `parallel()`.
+  `sys1 - sys2` is the difference of the outputs, for the error of a reduced model.
+* Provides `transform()` to change the states with a similarity transform, and
+  `to_controllable_form()` for controllable canonical form. `scale_time()` changes the unit of
+  the time axis.
+* Provides `place_poles()` for a state feedback gain with the chosen closed loop poles, and
+  `lqr()` for the optimal gain of a linear quadratic regulator. `compensator()` combines a
+  feedback gain and an observer gain into an observer based compensator.
+* Provides `augment_integrator()` to add integral-of-error states for a servo design, and
+  `with_input_delay()` to add a Pade approximation of a transport delay. `clone_resized()` embeds
+  a system in a larger one.
+* Provides `simulate()` to apply a sequence of inputs and collect the outputs, and
+  `step_response()` and `impulse_response()` for a quick look at a new design.
+  `simulate_timeseries()` plays back a recorded input with its own timestamps.
+* Provides `rise_time()`, `settling_time()`, and `overshoot()` to measure a step response.
+* Provides `quadratic_cost()` to evaluate `x^T Q x + u^T R u`, and **QuadraticCost** to total it
+  over a run.
+* Provides `from_linearization()` to linearize a nonlinear plant about an operating point.
+* Provides `from_tf()` to realize a SISO transfer function from its MATLAB `tf()` coefficients,
+  and `to_tf()` to recover the coefficients from a SISO system. `from_zpk()` realizes one from its
+  zeros, poles, and gain. `from_tf_matrix()` realizes a
+  MIMO system from a matrix of transfer functions.
+* **DynStateSpace** is a copy of a system with dynamically sized matrices, from `into_dynamic()`,
+  for an FFI or plugin boundary where the size is not known at compile time.
+* Provides `from_reader()` to read the matrices exported by a design tool from a sectioned text
+  format.
+* **TrajectoryLogger** records a simulation and writes it to a CSV file.
+* **TrajectoryRecorder** records a simulation in memory and compares a replay with it, for golden
+  tests.
+* **KalmanFilter** estimates the state of a system from noisy measurements of its output.
+* **Observer** is a Luenberger observer with a user designed gain `L`.
+* **GainScheduler** blends models designed at several operating points.
+* The **LtiSystem** trait steps any implementor, starting with `StateSpace`, from generic code.
 * All matrices and vectors use the format of [nalgebra] and are implemented as SMatrix objects.
-* Users can choose the data type (typically `f32` or `f64`) and size of the matrices using.
+* Users can choose the data type (typically `f32` or `f64`, or a fixed point type, see Example 3)
+  and size of the matrices using.
 * **SysVec** structure is provided to users to hold:
     * u, x, y vectors
     * Lower and upper bounds. Defaults are -9e99 and +9e99, respectively.
     * Setter methods, for convenience.
+* The optional `serde` feature serializes a `StateSpace` and a `SysVec`, see below.
+
+## no_std
+The crate is `no_std` when the default `std` feature is disabled, for bare-metal targets without
+an allocator. The `libm` feature provides the floating point functions that `std` would
+otherwise supply, and is needed for `to_discrete()`, `to_tustin()`, `feedback()`, and
+`dc_gain()`.
+```toml
+state_space = { version = "*", default-features = false, features = ["libm"] }
+```
+The methods which allocate are unavailable without `std`:
+* `eigenvalues()`, `is_stable()`, `check_euler_stability()`, `controllability_matrix()`,
+  `is_controllable()`, `observability_matrix()`, and `is_observable()`.
+* `simulate()`, `step_response()`, `impulse_response()`, and `simulate_timeseries()`.
+* `to_tf()`, `freq_response()`, and `freq_response_mimo()`.
+* `to_foh()`.
+* `controllability_gramian()`, `observability_gramian()`, and `lyapunov_p()`.
+* `hankel_singular_values()`, `balance_and_truncate()`, and `minreal()`.
+* `h2_norm()` and `hinf_norm()`.
+* `lqr()`.
+* `DynStateSpace` and `into_dynamic()`.
+* `from_reader()`.
+* `TrajectoryLogger` and `TrajectoryRecorder`.
+* `StateSpaceError` does not implement `std::error::Error`.
+
+## serde
+The optional `serde` feature implements `Serialize` and `Deserialize` for `StateSpace` and `SysVec`,
+to store a designed system as JSON or TOML and load it at boot. The matrices, the values and bounds
+of `u`, `x`, and `y`, `dt`, and the system time are stored. The integrator and the names are not: a
+loaded system steps with `ForwardEuler` until `set_integrator()` is called, and has no names. The
+size of each matrix is checked against the const generics, so a matrix of the wrong size is an error
+rather than a panic. A loaded system is checked with the setters, so a `dt` which is not positive is
+an error too. The feature also works with `no_std`.
+```toml
+state_space = { version = "*", features = ["serde"] }
+```
+```rust
+#[cfg(feature = "serde")]
+{
+    use nalgebra::matrix;
+    use state_space::{StateSpace, SysVec};
+
+    let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    sys.set_a(matrix![0.0, 1.0; -4.0, -2.0])
+        .set_b(matrix![0.0; 4.0])
+        .set_c(matrix![1.0, 0.0])
+        .set_u(SysVec::from_scalars(1.0, -2.0, 2.0))
+        .set_dt(0.01);
+    sys.update();
+
+    let json = serde_json::to_string(&sys).unwrap();
+    let loaded: StateSpace<f64, 1, 2, 1> = serde_json::from_str(&json).unwrap();
+    assert_eq!(sys.get_a(), loaded.get_a());
+    assert_eq!(sys.get_b(), loaded.get_b());
+    assert_eq!(sys.get_c(), loaded.get_c());
+    assert_eq!(sys.get_d(), loaded.get_d());
+    assert_eq!(sys.get_u(), loaded.get_u());
+    assert_eq!(sys.get_x(), loaded.get_x());
+    assert_eq!(sys.get_y(), loaded.get_y());
+    assert_eq!(sys.get_dt(), loaded.get_dt());
+    assert_eq!(json, serde_json::to_string(&loaded).unwrap());
+
+    // The same text is the wrong size for a system with 3 states.
+    assert!(serde_json::from_str::<StateSpace<f64, 1, 3, 1>>(&json).is_err());
+
+    // A negative dt is rejected as by set_dt().
+    let negative = json.replace("\"dt\":0.01", "\"dt\":-1.0");
+    assert_ne!(json, negative);
+    assert!(serde_json::from_str::<StateSpace<f64, 1, 2, 1>>(&negative).is_err());
+
+    // An output decimation of 0 updates the output every step, as set_output_decimation(0).
+    let zero = json.replace("\"output_decimation\":1", "\"output_decimation\":0");
+    assert_ne!(json, zero);
+    let mut loaded: StateSpace<f64, 1, 2, 1> = serde_json::from_str(&zero).unwrap();
+    loaded.update();
+
+    // A SysVec is checked as by from_vectors(), so a lower bound above the upper bound is an error.
+    let u = serde_json::to_string(&SysVec::<f64, 1>::from_scalars(1.0, -2.0, 2.0)).unwrap();
+    let crossed = u.replace("\"lb\":[-2.0]", "\"lb\":[3.0]");
+    assert_ne!(u, crossed);
+    assert!(serde_json::from_str::<SysVec<f64, 1>>(&crossed).is_err());
+}
+```
 
 ### Example 1:
 **SISO, first order system.**
@@ -104,19 +265,230 @@ fn main() {
 }
 ```
 
+### Example 3:
+**Fixed point, for a target without an FPU.**
+
+`update()` only needs the traits of the base bounds, `nalgebra::Scalar`, `ClosedAddAssign`,
+`ClosedMulAssign`, `PartialOrd`, `One`, `Zero`, and `NumCast`, so a fixed point type which
+implements them works, for example from the `fixed` crate or hand written as below. It does not
+need `Sub`, a subtraction is an addition of the value times -1 from `NumCast`.
+`NumCast::from()` should saturate a value out of range rather than return `None`, since the
+default bounds of `SysVec` are +/-9e99. The analysis and design methods need `RealField`, which a
+fixed point type does not implement, so the system is designed in `f64` and only stepped in fixed
+point.
+
+This example is a Q16.16 type, 16 integer and 16 fractional bits in an `i32`, which steps the
+system of Example 1 to within the resolution of the format.
+
+``` rust
+use core::ops::{Add, AddAssign, Mul, MulAssign};
+use nalgebra::matrix;
+use num_traits::{NumCast, One, ToPrimitive, Zero};
+use state_space::{StateSpace, SysVec};
+
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+struct Q16(i32);
+
+impl Add for Q16 {
+    type Output = Q16;
+    fn add(self, rhs: Q16) -> Q16 {
+        Q16(self.0.saturating_add(rhs.0))
+    }
+}
+impl Mul for Q16 {
+    type Output = Q16;
+    fn mul(self, rhs: Q16) -> Q16 {
+        let p = (self.0 as i64 * rhs.0 as i64) >> 16;
+        Q16(p.clamp(i32::MIN as i64, i32::MAX as i64) as i32)
+    }
+}
+impl AddAssign for Q16 {
+    fn add_assign(&mut self, rhs: Q16) {
+        *self = *self + rhs;
+    }
+}
+impl MulAssign for Q16 {
+    fn mul_assign(&mut self, rhs: Q16) {
+        *self = *self * rhs;
+    }
+}
+impl Zero for Q16 {
+    fn zero() -> Q16 {
+        Q16(0)
+    }
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+impl One for Q16 {
+    fn one() -> Q16 {
+        Q16(1 << 16)
+    }
+}
+impl ToPrimitive for Q16 {
+    fn to_i64(&self) -> Option<i64> {
+        Some((self.0 >> 16) as i64)
+    }
+    fn to_u64(&self) -> Option<u64> {
+        self.to_i64()?.to_u64()
+    }
+    fn to_f64(&self) -> Option<f64> {
+        Some(self.0 as f64 / 65536.0)
+    }
+}
+impl NumCast for Q16 {
+    // Saturates, so the default bounds of +/-9e99 become the range of Q16.
+    fn from<N: ToPrimitive>(n: N) -> Option<Q16> {
+        let v = (n.to_f64()? * 65536.0).round();
+        Some(Q16(v.clamp(i32::MIN as f64, i32::MAX as f64) as i32))
+    }
+}
+
+let q = |v: f64| <Q16 as NumCast>::from(v).unwrap();
+
+let mut fixed: StateSpace<Q16, 1, 1, 1> = StateSpace::new();
+fixed
+    .set_a(matrix![q(-1.0)])
+    .set_b(matrix![q(1.0)])
+    .set_c(matrix![q(1.0)])
+    .set_x(SysVec::from_val(0.1017))
+    .set_u(SysVec::from_val(1.0))
+    .set_dt(q(0.1));
+
+let mut float: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+float
+    .set_a(matrix![-1.0])
+    .set_b(matrix![1.0])
+    .set_c(matrix![1.0])
+    .set_x(SysVec::from_val(0.1017))
+    .set_u(SysVec::from_val(1.0))
+    .set_dt(0.1);
+
+for _ in 0..50 {
+    fixed.update();
+    float.update();
+    let y = fixed.get_y()[0].to_f64().unwrap();
+    assert!((y - float.get_y()[0]).abs() < 1e-3);
+}
+```
+
 
 */
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::needless_doctest_main)]
 
 // Use statements for dependencies.
+use core::time::Duration;
 use na::SMatrix;
 use nalgebra as na;
 use num_traits::{NumCast, One, Zero};
 
+use integrator::StepFn;
+
 // Use statements for re-exports.
+mod analysis;
+mod augment;
+mod builder;
+mod compare;
+mod cost;
+mod design;
+mod discretize;
+mod display;
+#[cfg(feature = "std")]
+mod dynamic;
+mod error;
+#[cfg(feature = "std")]
+mod frequency;
+#[cfg(feature = "std")]
+mod gramian;
+#[cfg(feature = "std")]
+mod import;
+mod integrator;
+mod interconnect;
+mod kalman;
+mod labels;
+mod linearize;
+#[cfg(feature = "std")]
+mod logger;
+mod lti;
+mod metrics;
+#[cfg(feature = "std")]
+mod norm;
+mod observer;
+mod ops;
+#[cfg(feature = "std")]
+mod recorder;
+#[cfg(feature = "std")]
+mod reduction;
+mod schedule;
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "std")]
+mod simulate;
 mod sys_vec;
+mod transfer_function;
+mod transform;
+mod validate;
+pub use builder::StateSpaceBuilder; // re-export.
+pub use cost::QuadraticCost; // re-export.
+#[cfg(feature = "std")]
+pub use dynamic::DynStateSpace; // re-export.
+pub use error::StateSpaceError; // re-export.
+pub use integrator::{ForwardEuler, Integrator, Rk4}; // re-export.
+pub use interconnect::{feedback, parallel, series, FeedbackSign}; // re-export.
+pub use kalman::KalmanFilter; // re-export.
+#[cfg(feature = "std")]
+pub use logger::TrajectoryLogger; // re-export.
+pub use lti::LtiSystem; // re-export.
+pub use metrics::{overshoot, rise_time, settling_time}; // re-export.
+pub use observer::Observer; // re-export.
+pub use ops::{Difference, Parallel, Series}; // re-export.
+#[cfg(feature = "std")]
+pub use recorder::TrajectoryRecorder; // re-export.
+pub use schedule::GainScheduler; // re-export.
 pub use sys_vec::SysVec;    // re-export.
+pub use validate::{is_positive_definite, is_symmetric}; // re-export.
+
+/// Describes whether the matrices of a `StateSpace` are continuous time (the default) or discrete
+/// time. A continuous system is integrated by `update()`, a discrete system is stepped directly
+/// with `x(n+1) = Ax + Bu`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SystemTime {
+    #[default]
+    Continuous,
+    Discrete,
+}
+
+/// Which input and state channels are at a bound after a step, see
+/// `StateSpace::last_saturation()`. Each element is `true` for a saturated channel.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SaturationReport<const NU: usize, const NX: usize> {
+    pub u: SMatrix<bool, NU, 1>,
+    pub x: SMatrix<bool, NX, 1>,
+}
+
+impl<const NU: usize, const NX: usize> SaturationReport<NU, NX> {
+    /// True if any input or state channel is saturated.
+    pub fn any(&self) -> bool {
+        self.u.iter().chain(self.x.iter()).any(|s| *s)
+    }
+}
 
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        remote = "Self",
+        bound(
+            serialize = "T: nalgebra::Scalar + serde::Serialize",
+            deserialize = "T: nalgebra::Scalar + nalgebra::ClosedAddAssign + \
+                nalgebra::ClosedMulAssign + PartialOrd + One + Zero + NumCast + \
+                serde::Deserialize<'de>"
+        )
+    )
+)]
 pub struct StateSpace<T, const NU: usize, const NX: usize, const NY: usize> {
     a: SMatrix<T, NX, NX>,
     b: SMatrix<T, NX, NU>,
@@ -125,15 +497,46 @@ pub struct StateSpace<T, const NU: usize, const NX: usize, const NY: usize> {
     u: SysVec<T, NU>,
     x: SysVec<T, NX>,
     y: SysVec<T, NY>,
-    pub dt: T,
+    dt: T,
+    time: SystemTime,
+    anti_windup: bool,
+    anti_windup_gain: T,
+    clamp_input: bool,
+    clamp_state: bool,
+    clamp_output: bool,
+    output_scale: SMatrix<T, NY, 1>,
+    output_decimation: usize,
+    output_countdown: usize,
+    frozen: bool,
+    closed_loop: bool,
+    feedback_gain: SMatrix<T, NU, NX>,
+    reference_gain: SMatrix<T, NU, NY>,
+    reference: SysVec<T, NY>,
+    #[cfg_attr(feature = "serde", serde(skip, default = "integrator::default_step"))]
+    integrator: StepFn<T, NU, NX>,
+    u_applied: SMatrix<T, NU, 1>,
+    y_raw: SMatrix<T, NY, 1>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    input_names: Option<[&'static str; NU]>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    state_names: Option<[&'static str; NX]>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    output_names: Option<[&'static str; NY]>,
+}
+
+// -1 as T. update() subtracts by adding a negated value, so it only needs the base bounds and a
+// fixed point type without `Sub` can be stepped, see Example 3.
+pub(crate) fn neg_one<T: NumCast>() -> T {
+    T::from(-1.0).expect("Conversion failed")
 }
 
+// The methods which do not compare values, so they are also available for a complex T, see
+// update_raw().
 impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
 where
     T: nalgebra::Scalar
         + nalgebra::ClosedAddAssign
         + nalgebra::ClosedMulAssign
-        + PartialOrd
         + One
         + Zero
         + NumCast,
@@ -178,6 +581,26 @@ where
             x,
             y,
             dt: T::one(),
+            time: SystemTime::Continuous,
+            anti_windup: false,
+            anti_windup_gain: T::one(),
+            clamp_input: true,
+            clamp_state: true,
+            clamp_output: true,
+            output_scale: SMatrix::from_element(T::one()),
+            output_decimation: 1,
+            output_countdown: 0,
+            frozen: false,
+            closed_loop: false,
+            feedback_gain: SMatrix::from_element(Zero::zero()),
+            reference_gain: SMatrix::from_element(Zero::zero()),
+            reference: SysVec::new(),
+            integrator: ForwardEuler::step,
+            u_applied: SMatrix::from_element(Zero::zero()),
+            y_raw: SMatrix::from_element(Zero::zero()),
+            input_names: None,
+            state_names: None,
+            output_names: None,
         }
     }
 
@@ -204,6 +627,81 @@ where
         self
     }
 
+    /// Sets A from a slice of `NX * NX` elements in column major order, the order of MATLAB's
+    /// `A(:)`, for matrices from generated code or a file. Returns
+    /// `StateSpaceError::DimensionMismatch`, and leaves A unchanged, if the slice has the wrong
+    /// length.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, StateSpaceError};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a_from_slice(&[0.0, -2.0, 1.0, -3.0]).unwrap();
+    /// assert_eq!(matrix![0.0, 1.0; -2.0, -3.0], sys.get_a());
+    ///
+    /// let err = sys.set_a_from_slice(&[1.0, 2.0, 3.0]).err();
+    /// assert_eq!(Some(StateSpaceError::DimensionMismatch), err);
+    /// assert_eq!(matrix![0.0, 1.0; -2.0, -3.0], sys.get_a());
+    /// ```
+    pub fn set_a_from_slice(&mut self, data: &[T]) -> Result<&mut Self, StateSpaceError> {
+        if data.len() != NX * NX {
+            return Err(StateSpaceError::DimensionMismatch);
+        }
+        Ok(self.set_a(SMatrix::from_column_slice(data)))
+    }
+
+    /// Sets B from a column major slice of `NX * NU` elements. Documentation is similar to
+    /// StateSpace.set_a_from_slice().
+    pub fn set_b_from_slice(&mut self, data: &[T]) -> Result<&mut Self, StateSpaceError> {
+        if data.len() != NX * NU {
+            return Err(StateSpaceError::DimensionMismatch);
+        }
+        Ok(self.set_b(SMatrix::from_column_slice(data)))
+    }
+
+    /// Sets C from a column major slice of `NY * NX` elements. Documentation is similar to
+    /// StateSpace.set_a_from_slice().
+    pub fn set_c_from_slice(&mut self, data: &[T]) -> Result<&mut Self, StateSpaceError> {
+        if data.len() != NY * NX {
+            return Err(StateSpaceError::DimensionMismatch);
+        }
+        Ok(self.set_c(SMatrix::from_column_slice(data)))
+    }
+
+    /// Sets D from a column major slice of `NY * NU` elements. Documentation is similar to
+    /// StateSpace.set_a_from_slice().
+    pub fn set_d_from_slice(&mut self, data: &[T]) -> Result<&mut Self, StateSpaceError> {
+        if data.len() != NY * NU {
+            return Err(StateSpaceError::DimensionMismatch);
+        }
+        Ok(self.set_d(SMatrix::from_column_slice(data)))
+    }
+
+    /// Setter for StateSpace.u. The rate limit of `vec`, see `SysVec::set_rate_limit()`, is
+    /// applied by `update()` relative to the input applied in the previous step, starting from
+    /// zero. The input is the commanded value, `get_u()` is not changed by the rate limit.
+    ///
+    /// A step command with a rate limit of 2 per time unit is ramped over 5 steps of 0.1.
+    ///
+    /// ```rust
+    /// use nalgebra::{SMatrix, SVector};
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// // y = u, so the output shows the applied input.
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_d(SMatrix::identity()).set_dt(0.1);
+    ///
+    /// let mut u = SysVec::from_val(1.0);
+    /// u.set_rate_limit(SVector::from_element(2.0));
+    /// sys.set_u(u);
+    ///
+    /// for exp_y in [0.2, 0.4, 0.6, 0.8, 1.0, 1.0] {
+    ///     sys.update();
+    ///     assert!((sys.get_y()[0] - exp_y).abs() < 1e-12);
+    /// }
+    /// assert_eq!(1.0, sys.get_u()[0]);
+    /// ```
     pub fn set_u(&mut self, vec: SysVec<T, NU>) -> &mut Self {
         self.u = vec;
         self
@@ -219,11 +717,6 @@ where
         self
     }
 
-    pub fn set_dt(&mut self, dt: T) -> &mut Self {
-        self.dt = dt;
-        self
-    }
-
     /// There are getter methods for the properties of StateSpace. This is a demo of
     /// `StateSpace.get_a()`, all other getters are similar.
     ///
@@ -282,33 +775,1269 @@ where
         self.y.get_val()
     }
 
+    /// The output `y = Cx + Du` of the last step before it was clamped to the bounds of `y`, the
+    /// output the system asks for. Comparing it with `get_y()` shows how far past saturation a
+    /// controller is pushing. It is the same as `get_y()` when the output is within its bounds,
+    /// or with `set_clamp_output(false)`.
+    ///
+    /// ```rust
+    /// use nalgebra::SMatrix;
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_d(SMatrix::from_element(4.0))
+    ///     .set_u(SysVec::from_val(1.0))
+    ///     .set_y(SysVec::from_scalars(0.0, -1.0, 1.0));
+    /// sys.update();
+    /// assert_eq!(1.0, sys.get_y()[0]);
+    /// assert_eq!(4.0, sys.get_y_raw()[0]);
+    ///
+    /// sys.set_u(SysVec::from_val(0.1)).update();
+    /// assert_eq!(sys.get_y(), sys.get_y_raw());
+    /// ```
+    pub fn get_y_raw(&self) -> SMatrix<T, NY, 1> {
+        self.y_raw.clone()
+    }
+
+    /// The state derivative `xDot = Ax + Bu` of the current `x` and `u`, without stepping the
+    /// system. A norm which grows from step to step points to an unstable system or a `dt` too
+    /// large for the integrator. For a discrete system this is `x(n+1)` rather than a
+    /// derivative. The value of `u` is used as set, before the rate limit of `update()`.
+    ///
+    /// This is the system of Example 1, at its initial condition.
+    ///
+    /// ```rust
+    /// use nalgebra::SMatrix;
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(-1.0 * SMatrix::identity())
+    ///     .set_b(SMatrix::identity())
+    ///     .set_c(SMatrix::identity())
+    ///     .set_dt(0.1)
+    ///     .set_x(SysVec::from_val(0.1017))
+    ///     .set_u(SysVec::from_val(1.0));
+    ///
+    /// // -1.0 * 0.1017 + 1.0 * 1.0
+    /// let x_dot = sys.state_derivative();
+    /// assert!((x_dot[0] - 0.8983).abs() < 1e-12);
+    ///
+    /// // Forward Euler steps along the derivative.
+    /// let x0 = sys.get_x();
+    /// sys.update();
+    /// assert!((sys.get_x() - (x0 + x_dot * 0.1)).norm() < 1e-12);
+    /// ```
+    pub fn state_derivative(&self) -> SMatrix<T, NX, 1> {
+        &self.a * self.x.val_ref() + &self.b * self.u.val_ref()
+    }
+
+    /// There are also accessors which return a reference rather than a clone, which avoids a
+    /// copy of each matrix in a tight control loop. This is a demo of `StateSpace.a_ref()`, the
+    /// other reference accessors are similar.
+    ///
+    /// ``` rust
+    /// use nalgebra::SMatrix;
+    /// use state_space::StateSpace;
+    /// type T = f64;
+    /// const NU: usize = 2;
+    /// const NX: usize = 16;
+    /// const NY: usize = 2;
+    ///
+    /// let mut my_ss: StateSpace<T, NU, NX, NY> = StateSpace::new();
+    /// my_ss.set_a(-1.0 * SMatrix::identity());
+    ///
+    /// // The same matrix as get_a(), without copying the 256 elements.
+    /// assert_eq!(&my_ss.get_a(), my_ss.a_ref());
+    /// assert!(std::ptr::eq(my_ss.a_ref(), my_ss.a_ref()));
+    /// ```
+    pub fn a_ref(&self) -> &SMatrix<T, NX, NX> {
+        &self.a
+    }
+
+    /// Reference accessor for StateSpace.b. Documentation is similar to StateSpace.a_ref().
+    pub fn b_ref(&self) -> &SMatrix<T, NX, NU> {
+        &self.b
+    }
+
+    /// Reference accessor for StateSpace.c. Documentation is similar to StateSpace.a_ref().
+    pub fn c_ref(&self) -> &SMatrix<T, NY, NX> {
+        &self.c
+    }
+
+    /// Reference accessor for StateSpace.d. Documentation is similar to StateSpace.a_ref().
+    pub fn d_ref(&self) -> &SMatrix<T, NY, NU> {
+        &self.d
+    }
+
+    /// Reference accessor for the value of StateSpace.u. Documentation is similar to
+    /// StateSpace.a_ref().
+    pub fn u_ref(&self) -> &SMatrix<T, NU, 1> {
+        self.u.val_ref()
+    }
+
+    /// Reference accessor for the value of StateSpace.x. Documentation is similar to
+    /// StateSpace.a_ref().
+    pub fn x_ref(&self) -> &SMatrix<T, NX, 1> {
+        self.x.val_ref()
+    }
+
+    /// Reference accessor for the value of StateSpace.y. Documentation is similar to
+    /// StateSpace.a_ref().
+    pub fn y_ref(&self) -> &SMatrix<T, NY, 1> {
+        self.y.val_ref()
+    }
+
+    /// Mutable reference to StateSpace.x, to change the state in place, for example to inject a
+    /// fault into one state. The bounds can be changed too. The new value is clamped at the start
+    /// of the next `update()`.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 2> = StateSpace::new();
+    /// sys.set_a(matrix![-1.0, 0.0; 0.0, -1.0])
+    ///     .set_c(matrix![1.0, 0.0; 0.0, 1.0])
+    ///     .set_x(SysVec::from_scalars(1.0, -2.0, 2.0))
+    ///     .set_dt(0.1);
+    ///
+    /// sys.get_x_mut().val_mut()[1] = 10.0;
+    /// assert_eq!(matrix![1.0; 10.0], sys.get_x());
+    ///
+    /// // The step starts from the new state, clamped to its upper bound of 2.0.
+    /// sys.update();
+    /// assert_eq!(matrix![1.0; 2.0], sys.get_y());
+    /// assert!((sys.get_x()[1] - 1.8).abs() < 1e-12);
+    /// ```
+    pub fn get_x_mut(&mut self) -> &mut SysVec<T, NX> {
+        &mut self.x
+    }
+
+    /// Mutable reference to StateSpace.u. Documentation is similar to StateSpace.get_x_mut().
+    pub fn get_u_mut(&mut self) -> &mut SysVec<T, NU> {
+        &mut self.u
+    }
+
+    /// Getter for StateSpace.dt, the time step. The field is private so that `dt` can only be
+    /// changed with `set_dt()` or `try_set_dt()`, which keep it positive.
+    ///
+    /// ```rust
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// assert_eq!(1.0, sys.get_dt());
+    /// sys.set_dt(0.25);
+    /// assert_eq!(0.25, sys.get_dt());
+    /// ```
+    ///
+    /// Writing the field directly does not compile:
+    ///
+    /// ```rust,compile_fail
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.dt = -1.0;
+    /// ```
+    pub fn get_dt(&self) -> T {
+        self.dt.clone()
+    }
+
+    /// The number of inputs, `NU`, for generic code which iterates over the channels. The
+    /// dimensions are `const fn`, so they can also size an array.
+    ///
+    /// ```rust
+    /// use state_space::StateSpace;
+    ///
+    /// type Sys = StateSpace<f64, 2, 3, 1>;
+    /// assert_eq!(2, Sys::num_inputs());
+    /// assert_eq!(3, Sys::num_states());
+    /// assert_eq!(1, Sys::num_outputs());
+    ///
+    /// const NX: usize = Sys::num_states();
+    /// let weights = [1.0; NX];
+    /// assert_eq!(3, weights.len());
+    /// ```
+    pub const fn num_inputs() -> usize {
+        NU
+    }
+
+    /// The number of states, `NX`, see `num_inputs()`.
+    pub const fn num_states() -> usize {
+        NX
+    }
+
+    /// The number of outputs, `NY`, see `num_inputs()`.
+    pub const fn num_outputs() -> usize {
+        NY
+    }
+
+    /// Reports whether the system matrices are continuous or discrete time. New systems are
+    /// continuous, `to_discrete()` returns a discrete system. See also `set_discrete()`.
+    pub fn get_system_time(&self) -> SystemTime {
+        self.time
+    }
+
+    /// Convenience check for `get_system_time() == SystemTime::Discrete`.
+    pub fn is_discrete(&self) -> bool {
+        self.time == SystemTime::Discrete
+    }
+
+    /// Marks the matrices as discrete time, for example matrices from MATLAB's `c2d`. The
+    /// matrices themselves are not changed, use `to_discrete()` to convert a continuous system.
+    /// `update()` then steps the system with `x(n+1) = Ax + Bu`.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, SMatrix};
+    /// use state_space::{StateSpace, SysVec, SystemTime};
+    ///
+    /// let ad = matrix![0.9, 0.1; -0.2, 0.7];
+    /// let bd = matrix![0.0; 0.5];
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(ad)
+    ///     .set_b(bd)
+    ///     .set_c(matrix![1.0, 0.0])
+    ///     .set_x(SysVec::from_val(1.0))
+    ///     .set_u(SysVec::from_val(2.0))
+    ///     .set_discrete();
+    /// assert_eq!(SystemTime::Discrete, sys.get_system_time());
+    ///
+    /// let mut x = sys.get_x();
+    /// for _ in 0..10 {
+    ///     x = ad * x + bd * 2.0;
+    ///     sys.update();
+    ///     assert_eq!(x, sys.get_x());
+    /// }
+    /// ```
+    pub fn set_discrete(&mut self) -> &mut Self {
+        self.time = SystemTime::Discrete;
+        self
+    }
+
+    /// Marks the matrices as continuous time, this is the default. The matrices themselves are
+    /// not changed. `update()` then integrates `xDot = Ax + Bu` with forward Euler.
+    pub fn set_continuous(&mut self) -> &mut Self {
+        self.time = SystemTime::Continuous;
+        self
+    }
+
+    /// Chooses the integrator `update()` uses for a continuous system, `ForwardEuler` by default.
+    /// See `Integrator` for an example of a new integrator.
+    ///
+    /// ```rust
+    /// use nalgebra::SMatrix;
+    /// use state_space::{Rk4, StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(-1.0 * SMatrix::identity())
+    ///     .set_x(SysVec::from_val(1.0))
+    ///     .set_dt(0.1);
+    /// let mut rk4 = sys;
+    ///
+    /// sys.set_integrator::<Rk4>().update();
+    /// rk4.update_rk4();
+    /// assert_eq!(rk4.get_x(), sys.get_x());
+    /// ```
+    pub fn set_integrator<I: Integrator<T, NU, NX>>(&mut self) -> &mut Self {
+        self.integrator = I::step;
+        self
+    }
+
+    /// Takes one raw step of the linear model: `x` advances by `xDot = Ax + Bu` with the
+    /// integrator, or to `x(n+1) = Ax + Bu` for a discrete system, and `y = Cx + Du` is scaled by
+    /// the output scale. This is not `update()`: the bounds of `u`, `x`, and `y`, the rate limit,
+    /// anti-windup, the output decimation, and the feedback are all skipped. Only the frozen flag
+    /// is honored. Since the step needs no ordering on `T`, it also works for a complex `T`, for
+    /// example to simulate the modes of a system separately.
+    ///
+    /// This example integrates the complex mode `xDot = (-1 + 2j) x` and compares it to the
+    /// exact solution `x(t) = exp((-1 + 2j) t)`. `set_dt()` compares `dt` to zero, so it is not
+    /// available for a complex `T`. Instead `dt` keeps its default of 1 and the time is scaled,
+    /// `A = (-1 + 2j) h` with the step `h = 0.01`.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, Complex};
+    /// use state_space::{Rk4, StateSpace, SysVec};
+    ///
+    /// let lambda = Complex::new(-1.0, 2.0);
+    /// let mut sys: StateSpace<Complex<f64>, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![lambda * 0.01])
+    ///     .set_c(matrix![Complex::new(1.0, 0.0)])
+    ///     .set_x(SysVec::from_val(1.0))
+    ///     .set_integrator::<Rk4>();
+    ///
+    /// for _ in 0..100 {
+    ///     sys.update_raw();
+    /// }
+    /// assert!((sys.get_x()[0] - lambda.exp()).norm() < 1e-7);
+    /// ```
+    pub fn update_raw(&mut self) -> &mut Self {
+        let u0 = self.u.val_ref();
+        let x0 = self.x.val_ref();
+        let yn = (&self.c * x0 + &self.d * u0).component_mul(&self.output_scale);
+        if !self.frozen {
+            let x1 = match self.time {
+                SystemTime::Continuous => {
+                    (self.integrator)(&self.a, &self.b, x0, u0, self.dt.clone())
+                }
+                SystemTime::Discrete => &self.a * x0 + &self.b * u0,
+            };
+            self.x.set_val(x1);
+        }
+        self.y_raw = yn.clone();
+        self.y.set_val(yn);
+        self
+    }
+}
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    /// Create a system from its four matrices and `dt` in one call, the same as `new()` followed
+    /// by `set_a()`, `set_b()`, `set_c()`, `set_d()`, and `try_set_dt()`. `u`, `x`, and `y` have
+    /// the defaults of `SysVec::new()`. Returns `StateSpaceError::NonPositiveDt` if `dt` is zero
+    /// or negative. See also `StateSpaceBuilder`, which allows `D` to be left out.
+    ///
+    /// This is the system of Example 1.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, StateSpaceError};
+    ///
+    /// let sys: StateSpace<f64, 1, 1, 1> =
+    ///     StateSpace::from_matrices(matrix![-1.0], matrix![1.0], matrix![1.0], matrix![0.0], 0.1)
+    ///         .unwrap();
+    /// assert_eq!(matrix![-1.0], sys.get_a());
+    /// assert_eq!(matrix![1.0], sys.get_b());
+    /// assert_eq!(matrix![1.0], sys.get_c());
+    /// assert_eq!(matrix![0.0], sys.get_d());
+    /// assert_eq!(0.1, sys.get_dt());
+    ///
+    /// let err = StateSpace::<f64, 1, 1, 1>::from_matrices(
+    ///     matrix![-1.0],
+    ///     matrix![1.0],
+    ///     matrix![1.0],
+    ///     matrix![0.0],
+    ///     0.0,
+    /// )
+    /// .err();
+    /// assert_eq!(Some(StateSpaceError::NonPositiveDt), err);
+    /// ```
+    pub fn from_matrices(
+        a: SMatrix<T, NX, NX>,
+        b: SMatrix<T, NX, NU>,
+        c: SMatrix<T, NY, NX>,
+        d: SMatrix<T, NY, NU>,
+        dt: T,
+    ) -> Result<Self, StateSpaceError> {
+        let mut sys = Self::new();
+        sys.set_a(a).set_b(b).set_c(c).set_d(d).try_set_dt(dt)?;
+        Ok(sys)
+    }
+
+    /// Setter for StateSpace.dt. Panics if `dt` is zero or negative, see `try_set_dt()` for a
+    /// version which returns an error instead.
+    ///
+    /// ```rust,should_panic
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_dt(0.0);
+    /// ```
+    pub fn set_dt(&mut self, dt: T) -> &mut Self {
+        self.try_set_dt(dt).expect("dt must be positive")
+    }
+
+    /// Fallible version of `set_dt()`. Returns `StateSpaceError::NonPositiveDt` if `dt` is zero
+    /// or negative, and leaves the current `dt` unchanged.
+    ///
+    /// ```rust
+    /// use state_space::{StateSpace, StateSpaceError};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// assert_eq!(Some(StateSpaceError::NonPositiveDt), sys.try_set_dt(0.0).err());
+    /// assert_eq!(Some(StateSpaceError::NonPositiveDt), sys.try_set_dt(-0.1).err());
+    /// assert_eq!(1.0, sys.get_dt());
+    ///
+    /// assert!(sys.try_set_dt(0.1).is_ok());
+    /// assert_eq!(0.1, sys.get_dt());
+    /// ```
+    pub fn try_set_dt(&mut self, dt: T) -> Result<&mut Self, StateSpaceError> {
+        if dt <= T::zero() {
+            return Err(StateSpaceError::NonPositiveDt);
+        }
+        self.dt = dt;
+        Ok(self)
+    }
+
+    /// Sets `dt` from a `Duration`, for example the period of a timer, converted to seconds in
+    /// `T`. Returns `StateSpaceError::ConversionFailed` if the number of seconds does not fit
+    /// in `T`, and `StateSpaceError::NonPositiveDt` for a zero duration. The current `dt` is
+    /// unchanged on an error.
+    ///
+    /// ```rust
+    /// use core::time::Duration;
+    /// use state_space::{StateSpace, StateSpaceError};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_dt_secs(Duration::from_millis(100)).unwrap();
+    /// assert_eq!(0.1, sys.get_dt());
+    ///
+    /// let err = sys.set_dt_secs(Duration::ZERO).err();
+    /// assert_eq!(Some(StateSpaceError::NonPositiveDt), err);
+    /// assert_eq!(0.1, sys.get_dt());
+    /// ```
+    pub fn set_dt_secs(&mut self, dt: Duration) -> Result<&mut Self, StateSpaceError> {
+        let secs = T::from(dt.as_secs_f64()).ok_or(StateSpaceError::ConversionFailed)?;
+        self.try_set_dt(secs)
+    }
+
+    /// Turns on back-calculation anti-windup, it is off by default. When the unclamped output
+    /// `y = Cx + Du` is outside the bounds of `y`, the states are driven back towards the bounds
+    /// by adding `gain * C^T * (y_clamped - y)` to `xDot` (or to x(n+1) for a discrete system).
+    /// Integrating states then stop winding up while the output is saturated, and the output
+    /// recovers as soon as the input reverses. The gain is set with `set_anti_windup_gain()`.
+    ///
+    /// Here an integrator with a saturated output is driven by a constant input. Without
+    /// anti-windup the state grows without limit, with it the state stays near the bound.
+    ///
+    /// ```rust
+    /// use nalgebra::SMatrix;
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_b(SMatrix::identity())
+    ///     .set_c(SMatrix::identity())
+    ///     .set_u(SysVec::from_val(1.0))
+    ///     .set_y(SysVec::from_scalars(0.0, -1.0, 1.0))
+    ///     .set_dt(0.1);
+    /// let mut sys_aw = sys;
+    /// sys_aw.set_anti_windup(true).set_anti_windup_gain(10.0);
+    ///
+    /// for _ in 0..200 {
+    ///     sys.update();
+    ///     sys_aw.update();
+    /// }
+    /// assert!(sys.get_x()[0] > 19.0);
+    /// assert!(sys_aw.get_x()[0] < 1.2);
+    /// assert_eq!(1.0, sys_aw.get_y()[0]);
+    /// ```
+    pub fn set_anti_windup(&mut self, enable: bool) -> &mut Self {
+        self.anti_windup = enable;
+        self
+    }
+
+    /// Sets the gain of the anti-windup back-calculation, the default is 1. A larger gain holds
+    /// the states closer to the bounds. For a continuous system `gain * dt` should be below 1 to
+    /// keep the correction itself stable. See `set_anti_windup()`.
+    pub fn set_anti_windup_gain(&mut self, gain: T) -> &mut Self {
+        self.anti_windup_gain = gain;
+        self
+    }
+
+    /// Turns the clamp of the output `y` to its bounds on or off, it is on by default. With the
+    /// clamp off, `get_y()` reports the unclamped `y = Cx + Du` and the bounds of `y` are only
+    /// used by anti-windup, see `set_anti_windup()`. The state is never affected.
+    ///
+    /// ```rust
+    /// use nalgebra::SMatrix;
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_d(SMatrix::identity())
+    ///     .set_u(SysVec::from_val(3.0))
+    ///     .set_y(SysVec::from_scalars(0.0, -1.0, 2.0));
+    ///
+    /// sys.update();
+    /// assert_eq!(2.0, sys.get_y()[0]);
+    ///
+    /// sys.set_clamp_output(false).update();
+    /// assert_eq!(3.0, sys.get_y()[0]);
+    /// ```
+    pub fn set_clamp_output(&mut self, enable: bool) -> &mut Self {
+        self.clamp_output = enable;
+        self
+    }
+
+    /// Turns the clamp of the input `u` to its bounds on or off, it is on by default. With the
+    /// clamp off, `update()` applies `u` as set, for a design which bounds the state but not
+    /// the input. The rate limit of `u` still applies. See `set_clamp_state()` for the state.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_b(matrix![1.0])
+    ///     .set_u(SysVec::from_scalars(3.0, -1.0, 1.0))
+    ///     .set_dt(0.5);
+    /// let mut unclamped = sys;
+    /// unclamped.set_clamp_input(false);
+    ///
+    /// sys.update();
+    /// unclamped.update();
+    /// assert_eq!(1.0, sys.get_u()[0]);
+    /// assert_eq!(0.5, sys.get_x()[0]);
+    /// assert_eq!(3.0, unclamped.get_u()[0]);
+    /// assert_eq!(1.5, unclamped.get_x()[0]);
+    /// ```
+    pub fn set_clamp_input(&mut self, enable: bool) -> &mut Self {
+        self.clamp_input = enable;
+        self
+    }
+
+    /// Turns the clamp of the state `x` to its bounds on or off, it is on by default. With the
+    /// clamp off, `update()` neither clamps `x(n)` before the step nor `x(n+1)` after it, for a
+    /// design which saturates the input but leaves the states unbounded. The bounds of `x` are
+    /// kept, and still used by `last_saturation()`. See `set_clamp_input()` for the input.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_b(matrix![1.0])
+    ///     .set_u(SysVec::from_val(1.0))
+    ///     .set_x(SysVec::from_scalars(0.0, -1.0, 1.0))
+    ///     .set_dt(0.5);
+    /// let mut unclamped = sys;
+    /// unclamped.set_clamp_state(false);
+    ///
+    /// sys.update_n(4);
+    /// unclamped.update_n(4);
+    /// assert_eq!(1.0, sys.get_x()[0]);
+    /// assert_eq!(2.0, unclamped.get_x()[0]);
+    /// ```
+    pub fn set_clamp_state(&mut self, enable: bool) -> &mut Self {
+        self.clamp_state = enable;
+        self
+    }
+
+    /// Sets a scale for each output, so `get_y()` reports `y = S (Cx + Du)` with the diagonal
+    /// matrix `S = diag(scale)`, for example to convert a normalized model to the engineering
+    /// units of the sensors. The scale is applied by `update()` and the other update methods
+    /// before the output is clamped, so the bounds of `y` and `get_y_raw()` are in the scaled
+    /// units too. The state is not affected. The default scale is one for every output.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, vector};
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 2> = StateSpace::new();
+    /// sys.set_a(matrix![-1.0])
+    ///     .set_b(matrix![1.0])
+    ///     .set_c(matrix![1.0; 1.0])
+    ///     .set_u(SysVec::from_val(1.0))
+    ///     .set_dt(0.1);
+    /// let mut scaled = sys;
+    /// scaled.set_output_scale(vector![2.0, 1.0]);
+    ///
+    /// sys.update_n(10);
+    /// scaled.update_n(10);
+    /// assert_eq!(sys.get_x(), scaled.get_x());
+    /// assert_eq!(2.0 * sys.get_y()[0], scaled.get_y()[0]);
+    /// assert_eq!(sys.get_y()[1], scaled.get_y()[1]);
+    /// ```
+    pub fn set_output_scale(&mut self, scale: SMatrix<T, NY, 1>) -> &mut Self {
+        self.output_scale = scale;
+        self
+    }
+
+    /// Sets the output decimation `k`, so `update()` and the other update methods, except
+    /// `update_raw()`, store the output only on every k-th step, while the state advances on
+    /// every step. This runs a model at a fast rate and reports it at a slow one, for example an
+    /// estimator at 1 kHz which is logged at 100 Hz with `k = 10`. Between outputs `get_y()` and
+    /// `get_y_raw()` return the most recent output, and `update_out()` and `step()` return it
+    /// too.
+    ///
+    /// The output is stored on the first step after this call or `reset_all()`, and every k-th
+    /// step after that. Anti-windup still uses the output of each step. The default, and a `k`
+    /// of zero, is to store the output on every step.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![-1.0])
+    ///     .set_b(matrix![1.0])
+    ///     .set_c(matrix![1.0])
+    ///     .set_u(SysVec::from_val(1.0))
+    ///     .set_dt(0.01)
+    ///     .set_output_decimation(5);
+    ///
+    /// for k in 0..20 {
+    ///     let (x0, y0) = (sys.get_x(), sys.get_y());
+    ///     sys.update();
+    ///     assert_ne!(x0, sys.get_x());
+    ///     if k % 5 == 0 {
+    ///         // y(n) is computed from x(n), before the step.
+    ///         assert_eq!(x0, sys.get_y());
+    ///     } else {
+    ///         assert_eq!(y0, sys.get_y());
+    ///     }
+    /// }
+    /// ```
+    pub fn set_output_decimation(&mut self, k: usize) -> &mut Self {
+        self.output_decimation = k.max(1);
+        self.output_countdown = 0;
+        self
+    }
+
+    /// Freezes the state, so `update()` and the other update methods still compute the output
+    /// `y = Cx + Du` from the current `x` and `u`, but do not advance `x`. This holds a
+    /// controller during a handoff or while gains are blended, without the transient of a
+    /// reset, and `set_frozen(false)` resumes from the held state. The matrices, the bounds, and
+    /// the rate limit of `u` are unchanged. The state is not frozen by default.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, vector};
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![-1.0])
+    ///     .set_b(matrix![1.0])
+    ///     .set_c(matrix![1.0])
+    ///     .set_d(matrix![0.5])
+    ///     .set_u(SysVec::from_val(1.0))
+    ///     .set_dt(0.1);
+    /// sys.update_n(10);
+    ///
+    /// sys.set_frozen(true);
+    /// let x = sys.get_x();
+    /// for u in [1.0, 2.0, -4.0] {
+    ///     sys.step(vector![u]);
+    ///     assert_eq!(x, sys.get_x());
+    ///     assert_eq!(x[0] + 0.5 * u, sys.get_y()[0]);
+    /// }
+    /// sys.update_raw();
+    /// assert_eq!(x, sys.get_x());
+    ///
+    /// sys.set_frozen(false).update();
+    /// assert_ne!(x, sys.get_x());
+    /// ```
+    pub fn set_frozen(&mut self, frozen: bool) -> &mut Self {
+        self.frozen = frozen;
+        self
+    }
+
+    /// True when the state is frozen, see `set_frozen()`.
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// Sets the state feedback gain `K` and closes the loop, so the input of each step is
+    /// `u = -K x + N r`, with the reference gain `N` and the reference `r` of
+    /// `set_reference_gain()` and `set_reference()`. The value of `u` set with `set_u()` is
+    /// replaced, its bounds and rate limit still apply. This turns a plant model into a closed
+    /// loop simulator, for example with a gain from `place_poles()` or `lqr()`. The loop is closed
+    /// by `update()`, `update_rk4()`, and `update_backward()`, and opened again with
+    /// `set_open_loop()`.
+    ///
+    /// For the plant `xDot = -x + u`, `y = x` with `K = 1` the closed loop is `xDot = -2 x + N r`,
+    /// with a steady state of `y = N r / 2`. `N = 2` makes the output track the reference.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![-1.0])
+    ///     .set_b(matrix![1.0])
+    ///     .set_c(matrix![1.0])
+    ///     .set_feedback_gain(matrix![1.0])
+    ///     .set_reference_gain(matrix![2.0])
+    ///     .set_reference(SysVec::from_val(3.0))
+    ///     .set_dt(0.01);
+    ///
+    /// sys.update_n(2000);
+    /// assert!((sys.get_y()[0] - 3.0).abs() < 1e-9);
+    /// assert!((sys.get_u()[0] - 3.0).abs() < 1e-9);
+    ///
+    /// // Without the reference gain the output settles at half the reference.
+    /// let mut half = sys;
+    /// half.set_reference_gain(matrix![1.0]).update_n(2000);
+    /// assert!((half.get_y()[0] - 1.5).abs() < 1e-9);
+    ///
+    /// // Open loop, the input set with set_u() is used again.
+    /// sys.set_open_loop().set_u(SysVec::from_val(0.0)).update_n(2000);
+    /// assert!(sys.get_y()[0].abs() < 1e-6);
+    /// ```
+    pub fn set_feedback_gain(&mut self, k: SMatrix<T, NU, NX>) -> &mut Self {
+        self.feedback_gain = k;
+        self.closed_loop = true;
+        self
+    }
+
+    /// Sets the reference gain `N` and closes the loop, see `set_feedback_gain()`. The gain is
+    /// zero until it is set.
+    pub fn set_reference_gain(&mut self, n: SMatrix<T, NU, NY>) -> &mut Self {
+        self.reference_gain = n;
+        self.closed_loop = true;
+        self
+    }
+
+    /// Sets the reference `r` of the closed loop, see `set_feedback_gain()`. The reference is
+    /// clamped to its bounds before it is used.
+    pub fn set_reference(&mut self, r: SysVec<T, NY>) -> &mut Self {
+        self.reference = r;
+        self
+    }
+
+    /// Opens the loop closed by `set_feedback_gain()` or `set_reference_gain()`, so `update()`
+    /// uses the input set with `set_u()` again. The gains are kept.
+    pub fn set_open_loop(&mut self) -> &mut Self {
+        self.closed_loop = false;
+        self
+    }
 
     /// Implements the forward-Euler equations for a continuous system. See examples above for a
-    /// demonstration.
+    /// demonstration. A discrete system is stepped directly with `x(n+1) = Ax + Bu`. Another
+    /// integrator can be chosen with `set_integrator()`.
+    ///
+    /// A step is done in this order:
+    /// 1. `x` is clamped to its bounds, in case it was set outside them. In closed loop `u` is
+    ///    set to `-K x + N r`, see `set_feedback_gain()`. `u` is clamped to its bounds. Either
+    ///    clamp can be turned off, see `set_clamp_state()` and `set_clamp_input()`.
+    /// 2. The output `y(n) = Cx(n) + Du(n)` is computed from the clamped `x(n)`, and scaled by
+    ///    `set_output_scale()`.
+    /// 3. `x(n+1)` is computed from the clamped `x(n)`, then clamped and stored.
+    /// 4. `y(n)` is clamped, see `set_clamp_output()`, and stored.
+    ///
+    /// So after a step `get_x()` returns `x(n+1)` and `get_y()` returns `y(n)`, one step behind.
+    /// With the default clamps both respect their bounds, and `y` is computed from a state
+    /// within bounds.
+    /// Here an integrator saturates part way through its 4th step, at 1.2, and is stored as 1.0.
+    /// The output catches up one step later.
+    ///
+    /// ```rust
+    /// use nalgebra::SMatrix;
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_b(SMatrix::identity())
+    ///     .set_c(SMatrix::identity())
+    ///     .set_u(SysVec::from_val(1.0))
+    ///     .set_x(SysVec::from_scalars(0.0, -1.0, 1.0))
+    ///     .set_dt(0.3);
+    ///
+    /// sys.update_n(4);
+    /// assert_eq!(1.0, sys.get_x()[0]);
+    /// assert!((sys.get_y()[0] - 0.9).abs() < 1e-12);
+    /// sys.update();
+    /// assert_eq!(1.0, sys.get_x()[0]);
+    /// assert_eq!(1.0, sys.get_y()[0]);
+    ///
+    /// // A state set outside its bounds is clamped before the output is computed.
+    /// sys.set_x(SysVec::from_scalars(5.0, -1.0, 1.0)).update();
+    /// assert_eq!(1.0, sys.get_y()[0]);
+    /// ```
+    ///
+    /// The matrices are borrowed rather than cloned, and all intermediate values are fixed size
+    /// nalgebra types on the stack, so a step never allocates. This example counts the heap
+    /// allocations made by a MIMO system over 1000 steps.
+    ///
+    /// ```rust
+    /// use nalgebra::SMatrix;
+    /// use state_space::{StateSpace, SysVec};
+    /// use std::alloc::{GlobalAlloc, Layout, System};
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// struct Counter;
+    /// static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// unsafe impl GlobalAlloc for Counter {
+    ///     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    ///         ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+    ///         System.alloc(layout)
+    ///     }
+    ///     unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    ///         System.dealloc(ptr, layout)
+    ///     }
+    /// }
+    ///
+    /// #[global_allocator]
+    /// static GLOBAL: Counter = Counter;
+    ///
+    /// let mut sys: StateSpace<f64, 4, 12, 6> = StateSpace::new();
+    /// sys.set_a(SMatrix::from_fn(|i, j| if i == j { -1.0 } else { 0.01 * j as f64 }))
+    ///     .set_b(SMatrix::from_element(0.1))
+    ///     .set_c(SMatrix::from_element(0.2))
+    ///     .set_u(SysVec::from_val(1.0))
+    ///     .set_anti_windup(true)
+    ///     .set_dt(0.01);
+    ///
+    /// let before = ALLOCATIONS.load(Ordering::SeqCst);
+    /// for _ in 0..1000 {
+    ///     sys.update();
+    ///     sys.update_rk4();
+    /// }
+    /// assert_eq!(before, ALLOCATIONS.load(Ordering::SeqCst));
+    /// ```
     pub fn update(&mut self) -> &mut Self {
-        // Apply forward-euler equations to move forward in time by dt time units.
-        // This is the continuous time version of the equation.
+        self.advance(self.integrator, self.dt.clone())
+    }
+
+    /// Same as `update()`, but returns the output `y(n)` by value instead of `&mut Self`, to save
+    /// a call to `get_y()` after each step.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![-1.0])
+    ///     .set_b(matrix![1.0])
+    ///     .set_c(matrix![1.0])
+    ///     .set_u(SysVec::from_val(1.0))
+    ///     .set_dt(0.1);
+    ///
+    /// for _ in 0..10 {
+    ///     let y = sys.update_out();
+    ///     assert_eq!(sys.get_y(), y);
+    /// }
+    /// assert!(sys.get_y()[0] > 0.5);
+    /// ```
+    pub fn update_out(&mut self) -> SMatrix<T, NY, 1> {
+        self.update().get_y()
+    }
+
+    /// Sets the value of `u`, calls `update()`, and returns `y`, for a real time loop which reads
+    /// one input sample and writes one output sample. The bounds and rate limit of `u` are kept
+    /// and applied as in `update()`.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, vector};
+    /// use state_space::StateSpace;
+    ///
+    /// let w = 2.0 * std::f64::consts::PI;
+    /// let z = 0.707_f64;
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; -w * w, -2.0 * z * w])
+    ///     .set_b(matrix![0.0; w * w])
+    ///     .set_c(matrix![1.0, 0.0])
+    ///     .set_dt(0.01);
+    /// let mut stream = sys;
+    ///
+    /// for y in sys.step_response(300, None) {
+    ///     assert_eq!(y, stream.step(vector![1.0]));
+    /// }
+    /// ```
+    pub fn step(&mut self, u: SMatrix<T, NU, 1>) -> SMatrix<T, NY, 1> {
+        self.u.set_val_keep_held(u);
+        self.update_out()
+    }
+
+    /// Calls `update()` with the input `u` for this step only, and leaves the value of `u` set
+    /// with `set_u()` as it was, for a what-if evaluation. The bounds and rate limit of `u` are
+    /// applied to a copy of `u` as in `update()`, so the stored `u`, and the elements held at a
+    /// bound by its clamp hysteresis, are unchanged. The rate limit of the next step starts from
+    /// the input applied here.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, vector};
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![-1.0])
+    ///     .set_b(matrix![1.0])
+    ///     .set_c(matrix![1.0])
+    ///     .set_u(SysVec::from_val(1.0))
+    ///     .set_dt(0.1);
+    /// let mut same = sys;
+    ///
+    /// sys.update_with(&vector![3.0]);
+    /// assert_eq!(vector![1.0], sys.get_u());
+    ///
+    /// // The state advanced with the input 3.0, not the stored 1.0.
+    /// same.set_u(SysVec::from_val(3.0)).update();
+    /// assert_eq!(same.get_x(), sys.get_x());
+    ///
+    /// // An input held at its upper bound by the clamp hysteresis stays held.
+    /// let mut u = SysVec::from_scalars(0.0, -1.0, 1.0);
+    /// u.set_clamp_hysteresis(vector![0.5]);
+    /// sys.set_u(u).step(vector![1.5]);
+    /// sys.update_with(&vector![0.0]);
+    /// sys.step(vector![0.8]);
+    /// assert_eq!(vector![1.0], sys.get_u());
+    /// ```
+    pub fn update_with(&mut self, u: &SMatrix<T, NU, 1>) -> &mut Self {
+        let mut input = self.u.clone();
+        input.set_val_keep_held(u.clone());
+        let stored = core::mem::replace(&mut self.u, input);
+        self.update();
+        self.u = stored;
+        self
+    }
+
+    /// Calls `update()` `n` times, with the input `u` held for all `n` steps.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let w = 2.0 * std::f64::consts::PI;
+    /// let z = 0.707_f64;
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; -w * w, -2.0 * z * w])
+    ///     .set_b(matrix![0.0; w * w])
+    ///     .set_c(matrix![1.0, 0.0])
+    ///     .set_u(SysVec::from_val(1.0))
+    ///     .set_dt(0.01);
+    /// let mut manual = sys;
+    ///
+    /// sys.update_n(10);
+    /// for _ in 0..10 {
+    ///     manual.update();
+    /// }
+    /// assert_eq!(manual.get_x(), sys.get_x());
+    /// assert_eq!(manual.get_y(), sys.get_y());
+    /// ```
+    pub fn update_n(&mut self, n: usize) -> &mut Self {
+        for _ in 0..n {
+            self.update();
+        }
+        self
+    }
+
+    /// Same as `update()`, and also returns the change in the state, `x(n+1) - x(n)`, and an
+    /// estimate of the local truncation error of a forward Euler step, for choosing `dt` in an
+    /// offline simulation. The estimate is the difference between the forward Euler step and a
+    /// 2nd order Heun (RK2) step from the same `x(n)`,
+    /// `dt^2 / 2 * A (A x + B u)`, as its largest absolute element. It falls by about 4 when `dt`
+    /// is halved. The state is stepped with the integrator of `update()`, see
+    /// `set_integrator()`. A discrete system has no integration error, and returns zero.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, vector};
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; -4.0, -1.0])
+    ///     .set_b(matrix![0.0; 4.0])
+    ///     .set_c(matrix![1.0, 0.0])
+    ///     .set_u(SysVec::from_val(1.0))
+    ///     .set_dt(0.02);
+    /// let mut half = sys;
+    /// half.set_dt(0.01);
+    ///
+    /// let (delta, error) = sys.update_with_error();
+    /// let (_, error_half) = half.update_with_error();
+    /// assert_eq!(sys.get_x(), delta);
+    /// assert!(error_half > 0.0);
+    /// assert!((error / error_half - 4.0).abs() < 0.1);
+    ///
+    /// // The change is from x(n) as clamped by update(), also with a clamp hysteresis on x.
+    /// let mut x = SysVec::from_scalars(0.5, -0.2, 0.2);
+    /// x.set_clamp_hysteresis(vector![0.1, 0.1]);
+    /// sys.set_x(x).set_u(SysVec::from_val(-1.0));
+    /// let (delta, _) = sys.update_with_error();
+    /// assert_eq!(sys.get_x() - vector![0.2, 0.2], delta);
+    /// ```
+    pub fn update_with_error(&mut self) -> (SMatrix<T, NX, 1>, T)
+    where
+        T: nalgebra::ClosedSubAssign,
+    {
+        // x(n) as advance() clamps it.
+        let mut x0 = self.x.clone();
+        if self.clamp_state {
+            x0.clamp_to_bounds();
+        }
+        let x0 = x0.get_val();
+        self.update();
+        let delta = self.x.get_val() - &x0;
+
+        let error = match self.time {
+            SystemTime::Continuous => {
+                let half = T::from(0.5).expect("Conversion failed");
+                let x_dot = &self.a * &x0 + &self.b * &self.u_applied;
+                let e = &self.a * x_dot * (self.dt.clone() * self.dt.clone() * half);
+                e.iter().fold(T::zero(), |max, v| {
+                    let abs = if *v < T::zero() {
+                        T::zero() - v.clone()
+                    } else {
+                        v.clone()
+                    };
+                    if abs > max {
+                        abs
+                    } else {
+                        max
+                    }
+                })
+            }
+            SystemTime::Discrete => T::zero(),
+        };
+        (delta, error)
+    }
+
+    /// Implements the classic 4th order Runge-Kutta equations for a continuous system, see `Rk4`,
+    /// whatever the integrator of `update()`. The input `u` is held constant over the step. The
+    /// output follows the same convention as `update()`, `y = Cx + Du` uses the clamped x(n), not
+    /// x(n+1), and the steps are done in the same order. A discrete system is stepped exactly as
+    /// in `update()`.
+    ///
+    /// RK4 is much more accurate than forward Euler for the same `dt`. This example compares both
+    /// against the analytic solution of `xDot = -x`, which is `x(t) = x(0) * exp(-t)`.
+    ///
+    /// ```rust
+    /// use nalgebra::SMatrix;
+    /// use state_space::{StateSpace, SysVec};
+    /// type T = f64;
+    ///
+    /// let mut euler: StateSpace<T, 1, 1, 1> = StateSpace::new();
+    /// euler
+    ///     .set_a(-1.0 * SMatrix::identity())
+    ///     .set_c(SMatrix::identity())
+    ///     .set_x(SysVec::from_val(1.0))
+    ///     .set_dt(0.1);
+    /// let mut rk4 = euler;
+    ///
+    /// for _ in 0..10 {
+    ///     euler.update();
+    ///     rk4.update_rk4();
+    /// }
+    ///
+    /// let exact = (-1.0_f64).exp();
+    /// let err_euler = (euler.get_x()[0] - exact).abs();
+    /// let err_rk4 = (rk4.get_x()[0] - exact).abs();
+    /// assert!(err_rk4 < 1e-6);
+    /// assert!(err_rk4 < err_euler / 1000.0);
+    /// ```
+    pub fn update_rk4(&mut self) -> &mut Self {
+        self.advance(Rk4::step, self.dt.clone())
+    }
+
+    /// Steps a continuous system backward in time, from x(n) to x(n-1), with the integrator of
+    /// `update()` and a step of `-dt`. This integrates `-xDot`, for the backward pass of a
+    /// two-filter smoother or an adjoint computation. Clamping, the rate limit, and the output
+    /// follow `update()`, `y = Cx + Du` uses the clamped x(n). The anti-windup term is part of
+    /// `xDot` and is reversed with it.
+    ///
+    /// Returns `StateSpaceError::SystemTimeMismatch` for a discrete system, which would need the
+    /// inverse of A to step backward.
+    ///
+    /// A forward step and a backward step cancel up to the truncation error of the integrator,
+    /// so with `Rk4` the system returns very close to where it started.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{Rk4, StateSpace, StateSpaceError, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; -4.0, -1.0])
+    ///     .set_b(matrix![0.0; 1.0])
+    ///     .set_c(matrix![1.0, 0.0])
+    ///     .set_x(SysVec::from_scalars(1.0, -10.0, 10.0))
+    ///     .set_u(SysVec::from_val(0.5))
+    ///     .set_integrator::<Rk4>()
+    ///     .set_dt(0.01);
+    /// let x0 = sys.get_x();
+    ///
+    /// for _ in 0..100 {
+    ///     sys.update();
+    /// }
+    /// assert!((sys.get_x() - x0).norm() > 0.1);
+    /// for _ in 0..100 {
+    ///     sys.update_backward().unwrap();
+    /// }
+    /// assert!((sys.get_x() - x0).norm() < 1e-9);
+    ///
+    /// sys.set_discrete();
+    /// let err = sys.update_backward().err();
+    /// assert_eq!(Some(StateSpaceError::SystemTimeMismatch), err);
+    /// ```
+    pub fn update_backward(&mut self) -> Result<&mut Self, StateSpaceError>
+    where
+        T: nalgebra::ClosedSubAssign,
+    {
+        if self.time == SystemTime::Discrete {
+            return Err(StateSpaceError::SystemTimeMismatch);
+        }
+        Ok(self.advance(self.integrator, T::zero() - self.dt.clone()))
+    }
+
+    // Steps the system once, with the integrator and the step dt for a continuous system. See
+    // update().
+    fn advance(&mut self, integrate: StepFn<T, NU, NX>, dt: T) -> &mut Self {
+        // Check x and u for clamp, update self. In closed loop u is found from the clamped x.
+        if self.clamp_state {
+            self.x.clamp_to_bounds();
+        }
+        self.apply_feedback();
+        if self.clamp_input {
+            self.u.clamp();
+        }
+
+        // References to x(n) and u(n). u(n) is rate limited.
+        self.apply_rate_limit();
+        let u0 = &self.u_applied;
+        let x0 = self.x.val_ref();
+
+        // Output equation, y = Cx + Du, scaled by set_output_scale(). It uses x(n), not x(n+1).
+        let yn = (&self.c * x0 + &self.d * u0).component_mul(&self.output_scale);
+
+        // x(n+1), unless the state is frozen. A discrete system has nothing to integrate. The
+        // anti-windup correction is added to xDot as a forward Euler term.
+        if !self.frozen {
+            let mut correction: SMatrix<T, NX, 1> = SMatrix::zeros();
+            self.add_anti_windup(&mut correction, &yn);
+
+            let x1 = match self.time {
+                SystemTime::Continuous => {
+                    integrate(&self.a, &self.b, x0, u0, dt.clone()) + correction * dt
+                }
+                SystemTime::Discrete => &self.a * x0 + &self.b * u0 + correction,
+            };
+            self.x.set_val(x1);
+            if self.clamp_state {
+                self.x.clamp_to_bounds();
+            }
+        }
+        if self.output_countdown == 0 {
+            self.set_output(yn);
+            self.output_countdown = self.output_decimation;
+        }
+        self.output_countdown = self.output_countdown.saturating_sub(1);
+
+        self
+    }
+
+    // Sets u to -K x + N r in closed loop, see set_feedback_gain(). Nothing is done in open loop.
+    fn apply_feedback(&mut self) {
+        if !self.closed_loop {
+            return;
+        }
+        self.reference.clamp();
+        let u = &self.reference_gain * self.reference.val_ref()
+            + &self.feedback_gain * self.x.val_ref() * neg_one::<T>();
+        self.u.set_val_keep_held(u);
+    }
 
-        // Check u and x for clamp, update self.
-        self.u.clamp();
-        self.x.clamp();
+    // Sets u_applied, the input applied by update(), to u limited to the rate limit of self.u
+    // relative to the input applied in the previous step. The input applied before the first
+    // step is zero.
+    fn apply_rate_limit(&mut self) {
+        self.u_applied = self.u.rate_limit(&self.u_applied, self.dt.clone());
+    }
 
-        // Local variables for x(n) and u(n).
-        let u0 = self.u.get_val();
-        let x0 = self.x.get_val();
+    // Stores the output y(n) as y_raw, and as y clamped to the bounds of y unless
+    // set_clamp_output(false).
+    fn set_output(&mut self, yn: SMatrix<T, NY, 1>) {
+        self.y_raw = yn.clone();
+        if self.clamp_output {
+            self.y.update(yn);
+        } else {
+            self.y.set_val(yn);
+        }
+    }
 
-        // Derivative equation. xDot = Ax + Bu.
-        let x_dot: SMatrix<T, NX, 1> =
-            (self.a.clone() * x0.clone()) + (self.b.clone() * u0.clone());
+    // Back-calculation anti-windup, see set_anti_windup(). Adds the correction to x_dot, nothing
+    // is added when anti-windup is off or the output yn is within its bounds.
+    fn add_anti_windup(&self, x_dot: &mut SMatrix<T, NX, 1>, yn: &SMatrix<T, NY, 1>) {
+        if !self.anti_windup {
+            return;
+        }
+        let excess = (self.y.clamped(yn) + yn * neg_one::<T>()).component_mul(&self.output_scale);
+        x_dot.gemv_tr(self.anti_windup_gain.clone(), &self.c, &excess, T::one());
+    }
 
-        // This is a super simple integrator, Forward Euler. Also known as x(n+1).
-        let x1 = x0.clone() + x_dot * self.dt.clone();
-        self.x = self.x.clone().update(x1).to_owned();
+    /// Reports which channels of `u` and `x` are at a bound, see `SysVec::is_saturated()`. Both
+    /// are clamped by `update()`, so after a step this shows the inputs which were limited and
+    /// the states `x(n+1)` which hit a bound, for fault monitoring.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, vector};
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_b(matrix![1.0; 0.5])
+    ///     .set_x(SysVec::from_scalars(0.0, -1.0, 1.0))
+    ///     .set_u(SysVec::from_scalars(1.0, -5.0, 5.0))
+    ///     .set_dt(0.25);
+    ///
+    /// sys.update_n(2);
+    /// assert!(!sys.last_saturation().any());
+    ///
+    /// // The first state reaches its upper bound of 1.0 on the 4th step, the second is at 0.5.
+    /// sys.update_n(2);
+    /// let report = sys.last_saturation();
+    /// assert!(report.any());
+    /// assert_eq!(vector![true, false], report.x);
+    /// assert_eq!(vector![false], report.u);
+    /// ```
+    pub fn last_saturation(&self) -> SaturationReport<NU, NX> {
+        SaturationReport {
+            u: self.u.is_saturated(),
+            x: self.x.is_saturated(),
+        }
+    }
 
-        // Output equation, y = Cx + Du. It uses x(n), not x(n+1), for forward euler technique.
-        let yn = (self.c.clone() * x0.clone()) + (self.d.clone() * u0.clone());
-        self.y = self.y.clone().update(yn).to_owned();
+    /// Sets the state `x` back to zeros. The bounds of `x` are preserved, as are `u` and `y`.
+    ///
+    /// ```rust
+    /// use nalgebra::{SMatrix, SVector};
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(-1.0 * SMatrix::identity())
+    ///     .set_b(SMatrix::identity())
+    ///     .set_c(SMatrix::identity())
+    ///     .set_x(SysVec::from_scalars(0.0, -2.0, 2.0))
+    ///     .set_u(SysVec::from_val(1.0))
+    ///     .set_dt(0.1);
+    /// for _ in 0..10 {
+    ///     sys.update();
+    /// }
+    /// assert!(sys.get_x()[0] > 0.0);
+    ///
+    /// sys.reset();
+    /// assert_eq!(SVector::<f64, 1>::zeros(), sys.get_x());
+    ///
+    /// // The upper bound of 2.0 still applies after the reset.
+    /// sys.set_u(SysVec::from_val(100.0));
+    /// for _ in 0..10 {
+    ///     sys.update();
+    /// }
+    /// assert_eq!(2.0, sys.get_x()[0]);
+    /// ```
+    pub fn reset(&mut self) -> &mut Self {
+        self.x.set_val(SMatrix::zeros());
+        self
+    }
 
+    /// Same as `reset()`, and also sets `u` and `y` back to zeros. All bounds are preserved. The
+    /// rate limit of `u` starts again from zero, and the next step stores the output, see
+    /// `set_output_decimation()`.
+    ///
+    /// ```rust
+    /// use nalgebra::{SMatrix, SVector};
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(-1.0 * SMatrix::identity())
+    ///     .set_b(SMatrix::identity())
+    ///     .set_c(SMatrix::identity())
+    ///     .set_u(SysVec::from_val(1.0));
+    /// sys.update().update();
+    ///
+    /// sys.reset_all();
+    /// assert_eq!(SVector::<f64, 1>::zeros(), sys.get_u());
+    /// assert_eq!(SVector::<f64, 1>::zeros(), sys.get_x());
+    /// assert_eq!(SVector::<f64, 1>::zeros(), sys.get_y());
+    /// ```
+    pub fn reset_all(&mut self) -> &mut Self {
+        self.reset();
+        self.u.set_val(SMatrix::zeros());
+        self.u_applied = SMatrix::zeros();
+        self.y.set_val(SMatrix::zeros());
+        self.y_raw = SMatrix::zeros();
+        self.output_countdown = 0;
         self
     }
 }
@@ -0,0 +1,91 @@
+/*!
+# linearize
+The `linearize` module builds a `StateSpace` system from a nonlinear plant by numerically
+linearizing it about an operating point.
+
+## Features.
+* `from_linearization()` computes `A`, `B`, `C`, and `D` as the Jacobians of the state dynamics
+  `xDot = f(x, u)` and the output `y = g(x, u)` with central finite differences.
+*/
+
+use na::{RealField, SVector};
+use nalgebra as na;
+use num_traits::NumCast;
+
+use crate::StateSpace;
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: RealField + NumCast,
+{
+    /// Linearizes the nonlinear plant `xDot = f(x, u)`, `y = g(x, u)` about the operating point
+    /// `(x0, u0)`. Each column of the Jacobians is the central difference
+    /// `(f(x0 + e_i * epsilon, u0) - f(x0 - e_i * epsilon, u0)) / (2 * epsilon)`, which has an
+    /// error proportional to `epsilon^2`. A value around `1e-5` times the scale of the states is
+    /// a good choice for `f64`.
+    ///
+    /// The returned system is continuous with the default `dt`, and its `u`, `x`, and `y` are
+    /// deviations from the operating point, `u - u0`, `x - x0`, and `y - g(x0, u0)`.
+    ///
+    /// This example linearizes a damped pendulum, `theta'' = -(g/l) sin(theta) - b theta' + u`,
+    /// about the downward equilibrium where the Jacobian is known analytically.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, vector, SVector};
+    /// use state_space::StateSpace;
+    ///
+    /// let (g_over_l, b) = (9.81 / 0.5, 0.2);
+    /// let f = |x: &SVector<f64, 2>, u: &SVector<f64, 1>| {
+    ///     vector![x[1], -g_over_l * x[0].sin() - b * x[1] + u[0]]
+    /// };
+    /// let g = |x: &SVector<f64, 2>, _u: &SVector<f64, 1>| vector![x[0]];
+    ///
+    /// let sys: StateSpace<f64, 1, 2, 1> =
+    ///     StateSpace::from_linearization(f, g, vector![0.0, 0.0], vector![0.0], 1e-5);
+    ///
+    /// let a = matrix![0.0, 1.0; -g_over_l, -b];
+    /// assert!((sys.get_a() - a).norm() < 1e-8);
+    /// assert!((sys.get_b() - matrix![0.0; 1.0]).norm() < 1e-8);
+    /// assert!((sys.get_c() - matrix![1.0, 0.0]).norm() < 1e-8);
+    /// assert!(sys.get_d().norm() < 1e-8);
+    /// ```
+    pub fn from_linearization<F, G>(
+        f: F,
+        g: G,
+        x0: SVector<T, NX>,
+        u0: SVector<T, NU>,
+        epsilon: T,
+    ) -> Self
+    where
+        F: Fn(&SVector<T, NX>, &SVector<T, NU>) -> SVector<T, NX>,
+        G: Fn(&SVector<T, NX>, &SVector<T, NU>) -> SVector<T, NY>,
+    {
+        let two_eps = epsilon.clone() + epsilon.clone();
+        let mut sys = Self::new();
+
+        // Columns of A and C, perturbing one state at a time.
+        for i in 0..NX {
+            let mut x_hi = x0.clone();
+            let mut x_lo = x0.clone();
+            x_hi[i] += epsilon.clone();
+            x_lo[i] -= epsilon.clone();
+            let df = (f(&x_hi, &u0) - f(&x_lo, &u0)) / two_eps.clone();
+            let dg = (g(&x_hi, &u0) - g(&x_lo, &u0)) / two_eps.clone();
+            sys.a.set_column(i, &df);
+            sys.c.set_column(i, &dg);
+        }
+
+        // Columns of B and D, perturbing one input at a time.
+        for j in 0..NU {
+            let mut u_hi = u0.clone();
+            let mut u_lo = u0.clone();
+            u_hi[j] += epsilon.clone();
+            u_lo[j] -= epsilon.clone();
+            let df = (f(&x0, &u_hi) - f(&x0, &u_lo)) / two_eps.clone();
+            let dg = (g(&x0, &u_hi) - g(&x0, &u_lo)) / two_eps.clone();
+            sys.b.set_column(j, &df);
+            sys.d.set_column(j, &dg);
+        }
+        sys
+    }
+}
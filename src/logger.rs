@@ -0,0 +1,179 @@
+/*!
+# logger
+The `logger` module contains `TrajectoryLogger`, which records the time, `u`, `x`, and `y` of a
+`StateSpace` system at each step and writes them to a CSV file for post-analysis.
+
+## Features.
+* `update()` steps a system and records it, `record()` records a system stepped elsewhere.
+* `write_csv()` writes to any `std::io::Write`, with a header row from the channel names.
+
+The samples are stored in a `Vec` and written with `std::io`, so this module requires the `std`
+feature.
+*/
+
+use std::fmt::Display;
+use std::io::{self, Write};
+
+use na::SMatrix;
+use nalgebra as na;
+use num_traits::{NumCast, One, Zero};
+
+use crate::StateSpace;
+
+// One row of the log.
+#[derive(Debug, Clone)]
+struct Sample<T, const NU: usize, const NX: usize, const NY: usize> {
+    t: T,
+    u: SMatrix<T, NU, 1>,
+    x: SMatrix<T, NX, 1>,
+    y: SMatrix<T, NY, 1>,
+}
+
+/// Records a trajectory of a `StateSpace` system.
+///
+/// Each row has the time, followed by `u`, `x`, and `y`, so a CSV file has `1 + NU + NX + NY`
+/// columns. The columns are named with the channel names of the system, see
+/// `set_input_names()`, or `u0`, `x0`, `y0` and so on for channels without names.
+///
+/// ```rust
+/// use nalgebra::matrix;
+/// use state_space::{StateSpace, SysVec, TrajectoryLogger};
+///
+/// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+/// sys.set_a(matrix![0.0, 1.0; -4.0, -2.0])
+///     .set_b(matrix![0.0; 4.0])
+///     .set_c(matrix![1.0, 0.0])
+///     .set_u(SysVec::from_val(1.0))
+///     .set_dt(0.1);
+/// sys.set_input_names(&["command"]).unwrap();
+///
+/// let mut logger = TrajectoryLogger::new();
+/// for _ in 0..5 {
+///     logger.update(&mut sys);
+/// }
+///
+/// let mut buffer: Vec<u8> = Vec::new();
+/// logger.write_csv(&mut buffer).unwrap();
+/// let csv = String::from_utf8(buffer).unwrap();
+/// let lines: Vec<&str> = csv.lines().collect();
+///
+/// assert_eq!(6, lines.len());
+/// assert_eq!("t,command,x0,x1,y0", lines[0]);
+/// assert!(lines.iter().all(|line| line.split(',').count() == 1 + 1 + 2 + 1));
+/// assert!(lines[1].starts_with("0.1,1,"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct TrajectoryLogger<T, const NU: usize, const NX: usize, const NY: usize> {
+    samples: Vec<Sample<T, NU, NX, NY>>,
+    t: T,
+    input_names: Option<[&'static str; NU]>,
+    state_names: Option<[&'static str; NX]>,
+    output_names: Option<[&'static str; NY]>,
+}
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> TrajectoryLogger<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    /// Create an empty logger, with the time starting at zero.
+    pub fn new() -> Self {
+        Self {
+            samples: Vec::new(),
+            t: T::zero(),
+            input_names: None,
+            state_names: None,
+            output_names: None,
+        }
+    }
+
+    /// Steps `sys` with `update()`, advances the time of the logger by `dt`, and records `sys`.
+    pub fn update(&mut self, sys: &mut StateSpace<T, NU, NX, NY>) -> &mut Self {
+        sys.update();
+        self.t += sys.dt.clone();
+        let t = self.t.clone();
+        self.record(t, sys)
+    }
+
+    /// Records `u`, `x`, and `y` of `sys` at the time `t`, for a system stepped some other way,
+    /// for example with `update_rk4()`. The channel names are taken from `sys`.
+    pub fn record(&mut self, t: T, sys: &StateSpace<T, NU, NX, NY>) -> &mut Self {
+        self.t = t.clone();
+        self.samples.push(Sample {
+            t,
+            u: sys.u.get_val(),
+            x: sys.x.get_val(),
+            y: sys.y.get_val(),
+        });
+        self.input_names = sys.input_names;
+        self.state_names = sys.state_names;
+        self.output_names = sys.output_names;
+        self
+    }
+
+    /// The number of recorded rows.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Writes a header row followed by one row per recorded sample.
+    pub fn write_csv<W: Write>(&self, mut w: W) -> io::Result<()>
+    where
+        T: Display,
+    {
+        write!(w, "t")?;
+        write_names(&mut w, "u", self.input_names)?;
+        write_names(&mut w, "x", self.state_names)?;
+        write_names(&mut w, "y", self.output_names)?;
+        writeln!(w)?;
+
+        for s in &self.samples {
+            write!(w, "{}", s.t)?;
+            for v in s.u.iter().chain(s.x.iter()).chain(s.y.iter()) {
+                write!(w, ",{}", v)?;
+            }
+            writeln!(w)?;
+        }
+        Ok(())
+    }
+}
+
+// Writes ",name" for each channel, or ",prefix<index>" when the channels have no names.
+fn write_names<W: Write, const N: usize>(
+    w: &mut W,
+    prefix: &str,
+    names: Option<[&'static str; N]>,
+) -> io::Result<()> {
+    for i in 0..N {
+        match names {
+            Some(names) => write!(w, ",{}", names[i])?,
+            None => write!(w, ",{}{}", prefix, i)?,
+        }
+    }
+    Ok(())
+}
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> Default
+    for TrajectoryLogger<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,105 @@
+/*!
+# lti
+The `lti` module contains the `LtiSystem` trait, a common interface for systems which take one
+input sample and return one output sample per step, so a test harness or controller loop can be
+written once for any of them.
+
+## Features.
+* The associated constants `NU`, `NX`, and `NY` are the number of inputs, states, and outputs.
+* `step()` applies an input and returns the output, `get_output()` returns the latest output,
+  and `reset()` starts a new run.
+* `StateSpace` is the first implementor, other types such as observers and filters can follow.
+
+The input and output are associated types, since an associated constant can not size an
+`SMatrix` on stable Rust. For a `StateSpace` they are `SVector<T, NU>` and `SVector<T, NY>`.
+*/
+
+use na::SMatrix;
+use nalgebra as na;
+use num_traits::{NumCast, One, Zero};
+
+use crate::StateSpace;
+
+/// A system which is stepped one input sample at a time.
+///
+/// This example runs a generic harness against a `StateSpace` system, a first order lag.
+///
+/// ```rust
+/// use nalgebra::{matrix, vector};
+/// use state_space::{LtiSystem, StateSpace};
+///
+/// // Holds the input for n steps and returns the last output.
+/// fn run<S: LtiSystem>(s: &mut S, u: S::Input, n: usize) -> S::Output {
+///     s.reset();
+///     for _ in 0..n {
+///         s.step(u.clone());
+///     }
+///     s.get_output()
+/// }
+///
+/// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+/// sys.set_a(matrix![-1.0]).set_b(matrix![2.0]).set_c(matrix![1.0]).set_dt(0.01);
+///
+/// assert_eq!((1, 1, 1), (
+///     <StateSpace<f64, 1, 1, 1> as LtiSystem>::NU,
+///     <StateSpace<f64, 1, 1, 1> as LtiSystem>::NX,
+///     <StateSpace<f64, 1, 1, 1> as LtiSystem>::NY,
+/// ));
+/// let y = run(&mut sys, vector![1.0], 2000);
+/// assert!((y[0] - 2.0).abs() < 1e-6);
+///
+/// // The harness resets the system, so a second run gives the same output.
+/// assert_eq!(y, run(&mut sys, vector![1.0], 2000));
+/// ```
+pub trait LtiSystem {
+    /// The number of inputs.
+    const NU: usize;
+    /// The number of states.
+    const NX: usize;
+    /// The number of outputs.
+    const NY: usize;
+    /// The input of one step.
+    type Input: Clone;
+    /// The output of one step.
+    type Output;
+
+    /// Applies the input `u` for one step and returns the output.
+    fn step(&mut self, u: Self::Input) -> Self::Output;
+
+    /// Returns the output of the most recent step.
+    fn get_output(&self) -> Self::Output;
+
+    /// Starts a new run from zero initial conditions.
+    fn reset(&mut self);
+}
+
+/// `step()` is `StateSpace::step()`, and `reset()` is `StateSpace::reset_all()`, which zeroes
+/// `u`, `x`, and `y` and keeps the bounds.
+impl<T, const NU: usize, const NX: usize, const NY: usize> LtiSystem for StateSpace<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    const NU: usize = NU;
+    const NX: usize = NX;
+    const NY: usize = NY;
+    type Input = SMatrix<T, NU, 1>;
+    type Output = SMatrix<T, NY, 1>;
+
+    fn step(&mut self, u: Self::Input) -> Self::Output {
+        StateSpace::step(self, u)
+    }
+
+    fn get_output(&self) -> Self::Output {
+        self.get_y()
+    }
+
+    fn reset(&mut self) {
+        self.reset_all();
+    }
+}
@@ -10,10 +10,6 @@ fn main() {
     const NX: usize = 1;
     const NY: usize = 1;
 
-    // // a matrix from a vector. Requires std.
-    // let v: Vec<T> = (1..=NX * NX).map(|x| x as T).collect(); // DEBUG, get a ^2 or ** or pow.
-    // let _a: SMatrix<T, NX, NX> = SMatrix::from_vec(v);
-
     // Create a SysVec.
     let svu: SysVec<T, NU> = SysVec::from_scalars(1.0, -9e99, 9e99);
     let x0: SysVec<T, NX> = SysVec::from_val(0.1);
@@ -26,6 +22,10 @@ fn main() {
         .set_x(x0)
         .set_dt(0.1);
 
+    // The matrices can also be set from flat, column major slices, as from generated code.
+    let a: [T; NX * NX] = [-1.0];
+    sys.set_a_from_slice(&a).expect("A has NX * NX elements");
+
     // Create a step input.
     sys.set_u(svu);
 
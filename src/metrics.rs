@@ -0,0 +1,127 @@
+/*!
+# metrics
+The `metrics` module computes the standard metrics of a step response, for tuning a controller
+against a specification.
+
+## Features.
+* `rise_time()` is the time to go from 10% to 90% of the final value.
+* `settling_time()` is the time after which the response stays within 2% of the final value.
+* `overshoot()` is the peak above the final value, in percent of the final value.
+
+The functions take one output channel of `step_response()`, as a slice, and `dt`. The sample `k`
+is the output at time `k * dt`, and the last sample is taken as the final value, so the response
+must be long enough to settle. They return `None` if the response is empty or its final value is
+zero.
+*/
+
+use na::RealField;
+use nalgebra as na;
+use num_traits::NumCast;
+
+// The response normalized by its final value, so it rises towards 1 for a negative final value
+// too. None for an empty response or a final value of zero.
+fn normalized<T: RealField>(y: &[T]) -> Option<impl Iterator<Item = T> + Clone + '_> {
+    let y_final = y.last()?.clone();
+    if y_final == T::zero() {
+        return None;
+    }
+    Some(y.iter().map(move |v| v.clone() / y_final.clone()))
+}
+
+/// The 10% to 90% rise time, the time from the first sample at 10% of the final value to the
+/// first sample at 90% of it.
+///
+/// A first order lag `1 / (s + 1)` has a rise time of `ln(9) = 2.197`.
+///
+/// ```rust
+/// use nalgebra::matrix;
+/// use state_space::{rise_time, StateSpace};
+///
+/// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+/// sys.set_a(matrix![-1.0]).set_b(matrix![1.0]).set_c(matrix![1.0]).set_dt(0.001);
+/// let y: Vec<f64> = sys.step_response(10000, None).iter().map(|y| y[0]).collect();
+///
+/// let tr = rise_time(&y, 0.001).unwrap();
+/// assert!((tr - 9.0_f64.ln()).abs() < 1e-2);
+/// assert_eq!(None, rise_time(&[0.0, 0.0], 0.001));
+/// ```
+pub fn rise_time<T: RealField + NumCast>(y: &[T], dt: T) -> Option<T> {
+    let low = T::from(0.1).expect("Conversion failed");
+    let high = T::from(0.9).expect("Conversion failed");
+    let y_n = normalized(y)?;
+    let k_low = y_n.clone().position(|v| v >= low)?;
+    let k_high = y_n.clone().position(|v| v >= high)?;
+    Some(T::from(k_high - k_low).expect("Conversion failed") * dt)
+}
+
+/// The 2% settling time, the time of the first sample after which the response stays within 2%
+/// of the final value.
+///
+/// A first order lag `1 / (s + 1)` settles at `ln(50) = 3.912`. The second order system of
+/// Example 2, with a damping of 0.707, settles near `4 / (z * w) = 0.9`.
+///
+/// ```rust
+/// use nalgebra::matrix;
+/// use state_space::{settling_time, StateSpace};
+///
+/// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+/// sys.set_a(matrix![-1.0]).set_b(matrix![1.0]).set_c(matrix![1.0]).set_dt(0.001);
+/// let y: Vec<f64> = sys.step_response(10000, None).iter().map(|y| y[0]).collect();
+/// let ts = settling_time(&y, 0.001).unwrap();
+/// assert!((ts - 50.0_f64.ln()).abs() < 1e-2);
+///
+/// let w = 2.0 * std::f64::consts::PI;
+/// let z = 0.707_f64;
+/// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+/// sys.set_a(matrix![0.0, 1.0; -w * w, -2.0 * z * w])
+///     .set_b(matrix![0.0; w * w])
+///     .set_c(matrix![1.0, 0.0])
+///     .set_dt(0.001);
+/// let y: Vec<f64> = sys.step_response(5000, None).iter().map(|y| y[0]).collect();
+/// let ts = settling_time(&y, 0.001).unwrap();
+/// assert!(ts > 0.5 && ts < 1.5);
+/// ```
+pub fn settling_time<T: RealField + NumCast>(y: &[T], dt: T) -> Option<T> {
+    let band = T::from(0.02).expect("Conversion failed");
+    let outside = normalized(y)?
+        .enumerate()
+        .filter(|(_, v)| (v.clone() - T::one()).abs() > band)
+        .last();
+    let k = outside.map_or(0, |(k, _)| k + 1);
+    Some(T::from(k).expect("Conversion failed") * dt)
+}
+
+/// The percent overshoot, `100 * (peak - final) / final`, or zero if the response does not go
+/// past its final value.
+///
+/// The second order system of Example 2 has a damping of `z = 0.707`, so its overshoot is
+/// `100 * exp(-pi z / sqrt(1 - z^2)) = 4.3%`. A first order lag has none.
+///
+/// ```rust
+/// use nalgebra::matrix;
+/// use state_space::{overshoot, Rk4, StateSpace};
+///
+/// let w = 2.0 * std::f64::consts::PI;
+/// let z = 0.707_f64;
+/// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+/// sys.set_a(matrix![0.0, 1.0; -w * w, -2.0 * z * w])
+///     .set_b(matrix![0.0; w * w])
+///     .set_c(matrix![1.0, 0.0])
+///     .set_integrator::<Rk4>()
+///     .set_dt(0.001);
+/// let y: Vec<f64> = sys.step_response(5000, None).iter().map(|y| y[0]).collect();
+///
+/// let exact = 100.0 * (-std::f64::consts::PI * z / (1.0 - z * z).sqrt()).exp();
+/// let os = overshoot(&y).unwrap();
+/// assert!(os > 0.0);
+/// assert!((os - exact).abs() < 1e-2);
+///
+/// let mut lag: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+/// lag.set_a(matrix![-1.0]).set_b(matrix![1.0]).set_c(matrix![1.0]).set_dt(0.01);
+/// let y: Vec<f64> = lag.step_response(1000, None).iter().map(|y| y[0]).collect();
+/// assert_eq!(Some(0.0), overshoot(&y));
+/// ```
+pub fn overshoot<T: RealField + NumCast>(y: &[T]) -> Option<T> {
+    let peak = normalized(y)?.fold(T::one(), |max, v| if v > max { v } else { max });
+    Some((peak - T::one()) * T::from(100.0).expect("Conversion failed"))
+}
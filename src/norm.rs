@@ -0,0 +1,278 @@
+/*!
+# norm
+The `norm` module computes the H2 and H-infinity norms of a stable `StateSpace` system, to
+compare controller designs with a single number.
+
+## Features.
+* `h2_norm()` is the RMS gain for white noise, computed from the controllability Gramian.
+* `hinf_norm()` is the peak gain over all frequencies, computed by bisection on the eigenvalues
+  of a Hamiltonian matrix.
+
+The eigenvalue problems are dynamically sized, so this module requires the `std` feature.
+*/
+
+use na::{Complex, DMatrix, RealField, SMatrix};
+use nalgebra as na;
+use num_traits::NumCast;
+
+use crate::{StateSpace, SystemTime};
+
+// Bisection stops when the bracket of the H-infinity norm is smaller than this, relative to the
+// upper bound.
+const HINF_REL_TOL: f64 = 1e-10;
+
+// The most steps of doubling the upper bound, and of bisection. A norm above 2^HINF_MAX_ITER is
+// treated as infinite.
+const HINF_MAX_ITER: usize = 200;
+
+// The largest singular value of a matrix, zero for an empty matrix.
+fn sigma_max<T: RealField>(m: &DMatrix<T>) -> T {
+    if m.is_empty() {
+        return T::zero();
+    }
+    m.clone().svd(false, false).singular_values.max()
+}
+
+// The largest singular value of G(jw) = C (jwI - A)^{-1} B + D of the continuous system (a, b,
+// c, d), infinite at a pole.
+fn gain_at<T: RealField>(
+    a: &DMatrix<T>,
+    b: &DMatrix<T>,
+    c: &DMatrix<T>,
+    d: &DMatrix<T>,
+    w: T,
+) -> T {
+    let to_complex = |m: &DMatrix<T>| m.map(|v| Complex::new(v, T::zero()));
+    let nx = a.nrows();
+    let jw = DMatrix::from_diagonal_element(nx, nx, Complex::new(T::zero(), w));
+    match (jw - to_complex(a)).try_inverse() {
+        Some(inv) => {
+            let g = to_complex(c) * inv * to_complex(b) + to_complex(d);
+            if g.is_empty() {
+                return T::zero();
+            }
+            g.svd(false, false).singular_values.max()
+        }
+        None => T::one() / T::zero(),
+    }
+}
+
+// True if the Hamiltonian matrix of the continuous system (a, b, c, d) for the level gamma has an
+// eigenvalue on the imaginary axis, which happens exactly when gamma is below the H-infinity
+// norm. With R = gamma^2 I - D^T D and Ae = A + B R^{-1} D^T C, the Hamiltonian is
+//   [ Ae                          B R^{-1} B^T ]
+//   [ -C^T (I + D R^{-1} D^T) C   -Ae^T        ]
+// The real part of an eigenvalue on the axis is only zero to within rounding, and a lightly
+// damped or very slow pole has an eigenvalue just as close to the axis above the norm. So each
+// eigenvalue near the axis is a candidate frequency, and gamma is only below the norm if the
+// gain at a candidate, or between two of them, reaches gamma.
+fn has_imaginary_eigenvalue<T: RealField>(
+    a: &DMatrix<T>,
+    b: &DMatrix<T>,
+    c: &DMatrix<T>,
+    d: &DMatrix<T>,
+    gamma: T,
+) -> bool {
+    let (nx, nu, ny) = (a.nrows(), b.ncols(), c.nrows());
+    let r = DMatrix::identity(nu, nu) * (gamma.clone() * gamma.clone()) - d.transpose() * d;
+    let r_inv = match r.try_inverse() {
+        Some(r_inv) => r_inv,
+        None => return true,
+    };
+    let ae = a + b * &r_inv * d.transpose() * c;
+
+    let mut h = DMatrix::zeros(2 * nx, 2 * nx);
+    h.view_mut((0, 0), (nx, nx)).copy_from(&ae);
+    h.view_mut((0, nx), (nx, nx))
+        .copy_from(&(b * &r_inv * b.transpose()));
+    h.view_mut((nx, 0), (nx, nx)).copy_from(
+        &(-c.transpose() * (DMatrix::identity(ny, ny) + d * &r_inv * d.transpose()) * c),
+    );
+    h.view_mut((nx, nx), (nx, nx)).copy_from(&(-ae.transpose()));
+
+    let tol = T::default_epsilon().sqrt();
+    let mut omegas = vec![T::zero()];
+    for e in h.complex_eigenvalues().iter() {
+        let scale = T::one() + e.re.clone().abs() + e.im.clone().abs();
+        if e.re.clone().abs() < tol.clone() * scale {
+            omegas.push(e.im.clone().abs());
+        }
+    }
+    omegas.sort_by(|w0, w1| w0.partial_cmp(w1).unwrap_or(core::cmp::Ordering::Equal));
+    let two = T::one() + T::one();
+    let mids = omegas
+        .windows(2)
+        .map(|w| (w[0].clone() + w[1].clone()) / two.clone());
+    omegas
+        .iter()
+        .cloned()
+        .chain(mids)
+        .any(|w| gain_at(a, b, c, d, w) >= gamma)
+}
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: RealField + NumCast,
+{
+    /// The H2 norm, `sqrt(trace(C W C^T))` where `W` is the controllability Gramian. A discrete
+    /// system adds the feedthrough, `sqrt(trace(C W C^T + D D^T))`.
+    ///
+    /// Returns `None` if the system is not stable, or if a continuous system has a non-zero `D`,
+    /// since the H2 norm is then infinite.
+    ///
+    /// The first order system `b / (s + a)` has an H2 norm of `b / sqrt(2 a)`, and the discrete
+    /// system `1 / (z - 0.5)` has an H2 norm of `sqrt(1 / (1 - 0.5^2))`.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![-4.0]).set_b(matrix![2.0]).set_c(matrix![1.0]);
+    /// let h2 = sys.h2_norm().unwrap();
+    /// assert!((h2 - 2.0 / 8.0_f64.sqrt()).abs() < 1e-12);
+    ///
+    /// sys.set_d(matrix![1.0]);
+    /// assert_eq!(None, sys.h2_norm());
+    ///
+    /// sys.set_a(matrix![0.5]).set_b(matrix![1.0]).set_d(matrix![0.0]).set_discrete();
+    /// let h2 = sys.h2_norm().unwrap();
+    /// assert!((h2 - (4.0_f64 / 3.0).sqrt()).abs() < 1e-12);
+    /// ```
+    pub fn h2_norm(&self) -> Option<T> {
+        let w = self.controllability_gramian().ok()?;
+        let mut m = self.c.clone() * w * self.c.transpose();
+        match self.time {
+            SystemTime::Continuous => {
+                if self.d.iter().any(|d| *d != T::zero()) {
+                    return None;
+                }
+            }
+            SystemTime::Discrete => m += self.d.clone() * self.d.transpose(),
+        }
+        Some(m.trace().sqrt())
+    }
+
+    /// The H-infinity norm, the peak over all frequencies of the largest singular value of
+    /// `G(jw)`. For a SISO system this is the peak of the Bode magnitude.
+    ///
+    /// The norm is bracketed from below by the gain at DC and at infinite frequency, and found
+    /// by bisection. For a level `gamma` above the feedthrough gain, `gamma` is below the norm
+    /// exactly when the associated Hamiltonian matrix has an eigenvalue on the imaginary axis.
+    /// Each eigenvalue near the axis is confirmed by the gain at its frequency, so a very slow
+    /// or lightly damped pole does not stall the search. A discrete system is first mapped to a
+    /// continuous system with the same norm by the bilinear transform `z = (1 + s) / (1 - s)`.
+    ///
+    /// Returns `None` if the system is not stable, or if no finite upper bound is found.
+    ///
+    /// The first order system `b / (s + a)` peaks at DC with `b / a`. The second order system
+    /// of Example 2 with a damping of 0.1 has a resonant peak of `1 / (2 z sqrt(1 - z^2))`.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![-4.0]).set_b(matrix![2.0]).set_c(matrix![1.0]);
+    /// assert!((sys.hinf_norm().unwrap() - 0.5).abs() < 1e-8);
+    ///
+    /// let w = 2.0 * std::f64::consts::PI;
+    /// let z = 0.1_f64;
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; -w * w, -2.0 * z * w])
+    ///     .set_b(matrix![0.0; w * w])
+    ///     .set_c(matrix![1.0, 0.0]);
+    /// let peak = 1.0 / (2.0 * z * (1.0 - z * z).sqrt());
+    /// assert!((sys.hinf_norm().unwrap() - peak).abs() < 1e-6 * peak);
+    ///
+    /// // The discrete system 1 / (z - 0.5) peaks at DC, z = 1, with a gain of 2.
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.5])
+    ///     .set_b(matrix![1.0])
+    ///     .set_c(matrix![1.0])
+    ///     .set_discrete();
+    /// assert!((sys.hinf_norm().unwrap() - 2.0).abs() < 1e-8);
+    ///
+    /// // An unstable system has no H-infinity norm.
+    /// sys.set_a(matrix![1.5]);
+    /// assert_eq!(None, sys.hinf_norm());
+    ///
+    /// // A very slow pole, b / (s + a) with a = b = 1e-9, peaks at DC with a gain of 1.
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![-1e-9]).set_b(matrix![1e-9]).set_c(matrix![1.0]);
+    /// assert!((sys.hinf_norm().unwrap() - 1.0).abs() < 1e-8);
+    ///
+    /// // A lightly damped pole pair, a damping of 1e-10 at 1 rad/s, peaks at 1 / (2 * 1e-10).
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![-1e-10, 1.0; -1.0, -1e-10])
+    ///     .set_b(matrix![0.0; 1.0])
+    ///     .set_c(matrix![1.0, 0.0]);
+    /// assert!((sys.hinf_norm().unwrap() - 5e9).abs() < 1e-6 * 5e9);
+    /// ```
+    pub fn hinf_norm(&self) -> Option<T> {
+        if !self.is_stable() {
+            return None;
+        }
+
+        // Continuous matrices with the same norm, see above for a discrete system.
+        let (a, b, c, d) = match self.time {
+            SystemTime::Continuous => (
+                self.a.clone(),
+                self.b.clone(),
+                self.c.clone(),
+                self.d.clone(),
+            ),
+            SystemTime::Discrete => {
+                let eye: SMatrix<T, NX, NX> = SMatrix::identity();
+                let m = (self.a.clone() + eye.clone()).try_inverse()?;
+                let sqrt2 = T::from(2.0).expect("Conversion failed").sqrt();
+                (
+                    m.clone() * (self.a.clone() - eye),
+                    m.clone() * self.b.clone() * sqrt2.clone(),
+                    self.c.clone() * m.clone() * sqrt2,
+                    self.d.clone() - self.c.clone() * m * self.b.clone(),
+                )
+            }
+        };
+        let a = DMatrix::from_iterator(NX, NX, a.iter().cloned());
+        let b = DMatrix::from_iterator(NX, NU, b.iter().cloned());
+        let c = DMatrix::from_iterator(NY, NX, c.iter().cloned());
+        let d = DMatrix::from_iterator(NY, NU, d.iter().cloned());
+
+        // Lower bound from the gain at infinite frequency, D, and at DC, D - C A^{-1} B.
+        let mut lower = sigma_max(&d);
+        if let Some(a_inv) = a.clone().try_inverse() {
+            lower = lower.max(sigma_max(&(&d - &c * a_inv * &b)));
+        }
+        if !lower.is_finite() {
+            return None;
+        }
+
+        // Double an upper bound until the Hamiltonian has no imaginary eigenvalues.
+        let two = T::from(2.0).expect("Conversion failed");
+        let mut upper = (lower.clone() * two.clone()).max(T::one());
+        let mut doublings = 0;
+        while has_imaginary_eigenvalue(&a, &b, &c, &d, upper.clone()) {
+            doublings += 1;
+            if doublings > HINF_MAX_ITER || !upper.is_finite() {
+                return None;
+            }
+            lower = upper.clone();
+            upper *= two.clone();
+        }
+
+        let tol = T::from(HINF_REL_TOL).expect("Conversion failed");
+        for _ in 0..HINF_MAX_ITER {
+            if upper.clone() - lower.clone() <= tol.clone() * upper.clone() {
+                break;
+            }
+            let mid = (lower.clone() + upper.clone()) / two.clone();
+            if has_imaginary_eigenvalue(&a, &b, &c, &d, mid.clone()) {
+                lower = mid;
+            } else {
+                upper = mid;
+            }
+        }
+        Some((lower + upper) / two)
+    }
+}
@@ -0,0 +1,118 @@
+/*!
+# observer
+The `observer` module contains `Observer`, a Luenberger observer which estimates the state of a
+`StateSpace` plant from its input and measured output, using an observer gain `L` designed
+offline (for example with MATLAB's `place()`).
+
+## Features.
+* Uses the `A`, `B`, `C`, and `D` matrices of the plant.
+* Follows the system time of the plant, a continuous observer is integrated with forward Euler
+  at the `dt` of the plant, a discrete observer is stepped directly.
+*/
+
+use na::SMatrix;
+use nalgebra as na;
+use num_traits::{NumCast, One, Zero};
+
+use crate::{StateSpace, SysVec, SystemTime};
+
+/// Luenberger observer with gain `L`. The estimate `x_hat` is corrected by the difference
+/// between the measured output and the predicted output `y_hat = C x_hat + D u`:
+/// * Continuous, `x_hat_dot = A x_hat + B u + L (y - y_hat)`, integrated with forward Euler.
+/// * Discrete, `x_hat(n+1) = A x_hat + B u + L (y - y_hat)`.
+///
+/// The estimation error `e = x - x_hat` follows `e(n+1) = (A - LC) e` for a discrete plant, so
+/// the observer converges when the eigenvalues of `A - LC` are stable.
+///
+/// This example observes a discrete double integrator. The gain places both poles of `A - LC`
+/// at 0.5, so the error decays by about half every step.
+///
+/// ```rust
+/// use nalgebra::{matrix, vector};
+/// use state_space::{Observer, StateSpace, SysVec};
+///
+/// let dt = 0.1;
+/// let mut plant: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+/// plant
+///     .set_a(matrix![1.0, dt; 0.0, 1.0])
+///     .set_b(matrix![0.0; dt])
+///     .set_c(matrix![1.0, 0.0])
+///     .set_dt(dt)
+///     .set_discrete();
+///
+/// // The observer starts at zero while the plant starts away from zero.
+/// let mut observer = Observer::new(plant, matrix![1.0; 0.25 / dt]);
+/// let mut x0: SysVec<f64, 2> = SysVec::new();
+/// x0.set_val(vector![1.0, -2.0]);
+/// plant.set_x(x0);
+///
+/// for n in 0..50 {
+///     let u = (n as f64 * 0.3).sin();
+///     plant.set_u(SysVec::from_val(u)).update();
+///     observer.update(vector![u], plant.get_y());
+/// }
+/// let error = plant.get_x() - observer.get_estimate().get_val();
+/// assert!(error.norm() < 1e-9);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct Observer<T, const NU: usize, const NX: usize, const NY: usize> {
+    sys: StateSpace<T, NU, NX, NY>,
+    l: SMatrix<T, NX, NY>,
+    x_hat: SysVec<T, NX>,
+}
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> Observer<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    /// Create an observer for `sys` with gain `l`. The initial estimate is the state `x` of
+    /// `sys`, which can be changed with `set_estimate()`.
+    pub fn new(sys: StateSpace<T, NU, NX, NY>, l: SMatrix<T, NX, NY>) -> Self {
+        let x_hat = sys.x.clone();
+        Self { sys, l, x_hat }
+    }
+
+    // Setters can be chained in the same way as StateSpace.
+    pub fn set_estimate(&mut self, x_hat: SysVec<T, NX>) -> &mut Self {
+        self.x_hat = x_hat;
+        self
+    }
+
+    pub fn set_l(&mut self, l: SMatrix<T, NX, NY>) -> &mut Self {
+        self.l = l;
+        self
+    }
+
+    // Getters return a copy, the same as StateSpace.
+    pub fn get_estimate(&self) -> SysVec<T, NX> {
+        self.x_hat.clone()
+    }
+
+    pub fn get_l(&self) -> SMatrix<T, NX, NY> {
+        self.l.clone()
+    }
+
+    /// Advances the estimate one step with the input `u` and the measured output `y`, both at
+    /// the same sample as the current estimate.
+    pub fn update(&mut self, u: SMatrix<T, NU, 1>, y: SMatrix<T, NY, 1>) -> &mut Self
+    where
+        T: nalgebra::ClosedSubAssign,
+    {
+        let sys = &self.sys;
+        let x0 = self.x_hat.val_ref();
+        let y_hat = &sys.c * x0 + &sys.d * &u;
+        let x_dot = &sys.a * x0 + &sys.b * &u + &self.l * (y - y_hat);
+        let x1 = match sys.time {
+            SystemTime::Continuous => x0 + x_dot * sys.dt.clone(),
+            SystemTime::Discrete => x_dot,
+        };
+        self.x_hat.update(x1);
+        self
+    }
+}
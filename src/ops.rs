@@ -0,0 +1,236 @@
+/*!
+# ops
+The `ops` module implements the `*` and `+` operators on `StateSpace` systems, as a shorthand for
+`series()` and `parallel()`.
+
+## Features.
+* `sys2 * sys1` is the series connection `u -> [sys1] -> [sys2] -> y`. The signal flows right to
+  left, as for the product of transfer functions `G2(s) G1(s)` or of matrices `M2 M1 u`.
+* `sys1 + sys2` is the parallel connection, the sum of the outputs of both systems.
+* `sys1 - sys2` is the difference of the outputs of both systems for the same input, the parallel
+  connection with the `C` and `D` of `sys2` negated. For example `full - reduced` is the error
+  of a reduced model, whose `hinf_norm()` is the worst case error over all frequencies.
+
+The number of states of the combined system is a separate const generic, which an operator can
+not choose. So an operator returns a `Series` or `Parallel`, which holds a copy of both systems,
+and is converted with `try_into()` to a system with the state dimension of the annotation:
+`let sys: StateSpace<f64, 1, 2, 1> = (sys2 * sys1).try_into()?;`. The errors are those of
+`series()` and `parallel()`, a `Difference` has the errors of `parallel()`. A chain of more than
+two systems is converted one product at a time.
+*/
+
+use core::ops::{Add, Mul, Sub};
+
+use nalgebra::SMatrix;
+use num_traits::{NumCast, One, Zero};
+
+use crate::{parallel, series, StateSpace, StateSpaceError};
+
+/// The series connection `second * first`, see the `*` operator on `StateSpace`.
+///
+/// ```rust
+/// use nalgebra::matrix;
+/// use state_space::{StateSpace, SysVec};
+///
+/// let mut sys1: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+/// sys1.set_a(matrix![-1.0]).set_b(matrix![1.0]).set_c(matrix![1.0]).set_dt(0.1);
+/// let mut sys2: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+/// sys2.set_a(matrix![-2.0]).set_b(matrix![2.0]).set_c(matrix![1.0]).set_dt(0.1);
+///
+/// // u -> sys1 -> sys2, written right to left.
+/// let mut sys: StateSpace<f64, 1, 2, 1> = (sys2 * sys1).try_into().unwrap();
+///
+/// sys.set_u(SysVec::from_val(1.0));
+/// sys1.set_u(SysVec::from_val(1.0));
+/// for _ in 0..50 {
+///     sys.update();
+///     sys1.update();
+///     sys2.set_u(*SysVec::new().set_val(sys1.get_y()));
+///     sys2.update();
+///     assert!((sys.get_y() - sys2.get_y()).abs().max() < 1e-12);
+/// }
+///
+/// // A wrong number of states, or a different dt, is an error.
+/// let result: Result<StateSpace<f64, 1, 3, 1>, _> = (sys2 * sys1).try_into();
+/// assert!(result.is_err());
+/// sys2.set_dt(0.2);
+/// let result: Result<StateSpace<f64, 1, 2, 1>, _> = (sys2 * sys1).try_into();
+/// assert!(result.is_err());
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct Series<
+    T,
+    const NU: usize,
+    const NX1: usize,
+    const NM: usize,
+    const NX2: usize,
+    const NY: usize,
+> {
+    first: StateSpace<T, NU, NX1, NM>,
+    second: StateSpace<T, NM, NX2, NY>,
+}
+
+/// The parallel connection `first + second`, see the `+` operator on `StateSpace`.
+///
+/// ```rust
+/// use nalgebra::matrix;
+/// use state_space::{StateSpace, SysVec};
+///
+/// let mut sys1: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+/// sys1.set_a(matrix![-1.0]).set_b(matrix![2.0]).set_c(matrix![1.0]).set_dt(0.05);
+/// let mut sys2: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+/// sys2.set_a(matrix![-4.0]).set_b(matrix![4.0]).set_c(matrix![3.0]).set_dt(0.05);
+///
+/// // The DC gain is the sum of the DC gains, 2 + 3.
+/// let mut sys: StateSpace<f64, 1, 2, 1> = (sys1 + sys2).try_into().unwrap();
+/// sys.set_u(SysVec::from_val(1.0));
+/// for _ in 0..1000 {
+///     sys.update();
+/// }
+/// assert!((sys.get_y()[0] - 5.0).abs() < 1e-9);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct Parallel<T, const NU: usize, const NX1: usize, const NX2: usize, const NY: usize> {
+    first: StateSpace<T, NU, NX1, NY>,
+    second: StateSpace<T, NU, NX2, NY>,
+}
+
+/// The difference `first - second`, see the `-` operator on `StateSpace`.
+///
+/// ```rust
+/// use nalgebra::matrix;
+/// use state_space::{StateSpace, SysVec};
+///
+/// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+/// sys.set_a(matrix![0.0, 1.0; -2.0, -3.0])
+///     .set_b(matrix![0.0; 1.0])
+///     .set_c(matrix![1.0, 0.5])
+///     .set_d(matrix![0.1])
+///     .set_dt(0.01);
+///
+/// // The difference of a system with itself is zero for any input, up to round off.
+/// let mut error: StateSpace<f64, 1, 4, 1> = (sys - sys).try_into().unwrap();
+/// for k in 0..500 {
+///     error.set_u(SysVec::from_val((0.02 * k as f64).sin()));
+///     error.update();
+///     assert!(error.get_y()[0].abs() < 1e-12);
+/// }
+///
+/// // A first order model of the same DC gain has an error which settles at zero.
+/// let mut reduced: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+/// reduced.set_a(matrix![-1.0]).set_b(matrix![0.5]).set_c(matrix![1.0]).set_dt(0.01);
+/// let mut error: StateSpace<f64, 1, 3, 1> = (sys - reduced).try_into().unwrap();
+/// error.set_u(SysVec::from_val(1.0)).update_n(2000);
+/// assert!((error.get_y()[0] - 0.1).abs() < 1e-6);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct Difference<T, const NU: usize, const NX1: usize, const NX2: usize, const NY: usize> {
+    first: StateSpace<T, NU, NX1, NY>,
+    second: StateSpace<T, NU, NX2, NY>,
+}
+
+impl<T, const NU: usize, const NX1: usize, const NM: usize, const NX2: usize, const NY: usize>
+    Mul<StateSpace<T, NU, NX1, NM>> for StateSpace<T, NM, NX2, NY>
+{
+    type Output = Series<T, NU, NX1, NM, NX2, NY>;
+
+    fn mul(self, rhs: StateSpace<T, NU, NX1, NM>) -> Self::Output {
+        Series {
+            first: rhs,
+            second: self,
+        }
+    }
+}
+
+impl<T, const NU: usize, const NX1: usize, const NX2: usize, const NY: usize>
+    Add<StateSpace<T, NU, NX2, NY>> for StateSpace<T, NU, NX1, NY>
+{
+    type Output = Parallel<T, NU, NX1, NX2, NY>;
+
+    fn add(self, rhs: StateSpace<T, NU, NX2, NY>) -> Self::Output {
+        Parallel {
+            first: self,
+            second: rhs,
+        }
+    }
+}
+
+impl<T, const NU: usize, const NX1: usize, const NX2: usize, const NY: usize>
+    Sub<StateSpace<T, NU, NX2, NY>> for StateSpace<T, NU, NX1, NY>
+{
+    type Output = Difference<T, NU, NX1, NX2, NY>;
+
+    fn sub(self, rhs: StateSpace<T, NU, NX2, NY>) -> Self::Output {
+        Difference {
+            first: self,
+            second: rhs,
+        }
+    }
+}
+
+impl<
+        T,
+        const NU: usize,
+        const NX1: usize,
+        const NM: usize,
+        const NX2: usize,
+        const NY: usize,
+        const NX: usize,
+    > TryFrom<Series<T, NU, NX1, NM, NX2, NY>> for StateSpace<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    type Error = StateSpaceError;
+
+    fn try_from(s: Series<T, NU, NX1, NM, NX2, NY>) -> Result<Self, Self::Error> {
+        series(&s.first, &s.second)
+    }
+}
+
+impl<T, const NU: usize, const NX1: usize, const NX2: usize, const NY: usize, const NX: usize>
+    TryFrom<Parallel<T, NU, NX1, NX2, NY>> for StateSpace<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    type Error = StateSpaceError;
+
+    fn try_from(p: Parallel<T, NU, NX1, NX2, NY>) -> Result<Self, Self::Error> {
+        parallel(&p.first, &p.second)
+    }
+}
+
+impl<T, const NU: usize, const NX1: usize, const NX2: usize, const NY: usize, const NX: usize>
+    TryFrom<Difference<T, NU, NX1, NX2, NY>> for StateSpace<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + nalgebra::ClosedSubAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    type Error = StateSpaceError;
+
+    fn try_from(diff: Difference<T, NU, NX1, NX2, NY>) -> Result<Self, Self::Error> {
+        let mut negated = diff.second;
+        let (c, d) = (negated.c.clone(), negated.d.clone());
+        negated
+            .set_c(SMatrix::zeros() - c)
+            .set_d(SMatrix::zeros() - d);
+        parallel(&diff.first, &negated)
+    }
+}
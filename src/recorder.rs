@@ -0,0 +1,177 @@
+/*!
+# recorder
+The `recorder` module contains `TrajectoryRecorder`, which keeps the `u`, `x`, and `y` of each step
+of a `StateSpace` system in memory, for golden tests: a trajectory recorded from a known good
+build is compared with a replay of the same inputs on the code under test.
+
+## Features.
+* `update()` steps a system and records it, `record()` records a system stepped elsewhere.
+* `replay()` applies the recorded inputs to a system and records the new trajectory.
+* `first_mismatch()` compares two trajectories within a tolerance, and `matches()` is true when
+  they agree.
+
+Unlike `TrajectoryLogger`, nothing is written to a file. The steps are stored in a `Vec`, so this
+module requires the `std` feature.
+*/
+
+use na::SMatrix;
+use nalgebra as na;
+use num_traits::{NumCast, One, Zero};
+
+use crate::StateSpace;
+
+// One step of the trajectory.
+#[derive(Debug, Clone, PartialEq)]
+struct Step<T, const NU: usize, const NX: usize, const NY: usize> {
+    u: SMatrix<T, NU, 1>,
+    x: SMatrix<T, NX, 1>,
+    y: SMatrix<T, NY, 1>,
+}
+
+/// Records the trajectory of a `StateSpace` system for a golden test.
+///
+/// Each step holds the value of `u` applied by the step, and the `x` and `y` after it. The value
+/// of `u` is the one set with `set_u()`, before the rate limit, so `replay()` reproduces a run
+/// with a rate limit too.
+///
+/// ```rust
+/// use nalgebra::matrix;
+/// use state_space::{StateSpace, SysVec, TrajectoryRecorder};
+///
+/// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+/// sys.set_a(matrix![0.0, 1.0; -4.0, -2.0])
+///     .set_b(matrix![0.0; 4.0])
+///     .set_c(matrix![1.0, 0.0])
+///     .set_dt(0.01);
+/// let start = sys;
+///
+/// // The baseline, with a varying input.
+/// let mut baseline = TrajectoryRecorder::new();
+/// for k in 0..200 {
+///     sys.set_u(SysVec::from_val((0.05 * k as f64).sin()));
+///     baseline.update(&mut sys);
+/// }
+/// assert_eq!(200, baseline.len());
+///
+/// // The same inputs from the same start reproduce the baseline exactly.
+/// let mut replay = start;
+/// let run = baseline.replay(&mut replay);
+/// assert!(run.matches(&baseline, 0.0));
+///
+/// // A changed model is caught at the first step which differs by more than the tolerance.
+/// let mut changed = start;
+/// changed.set_b(matrix![0.0; 4.1]);
+/// let run = baseline.replay(&mut changed);
+/// // The first input is zero, so the first step which differs is the second.
+/// assert!(!run.matches(&baseline, 1e-6));
+/// assert_eq!(Some(1), run.first_mismatch(&baseline, 1e-6));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrajectoryRecorder<T, const NU: usize, const NX: usize, const NY: usize> {
+    steps: Vec<Step<T, NU, NX, NY>>,
+}
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> TrajectoryRecorder<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    /// Create an empty recorder.
+    pub fn new() -> Self {
+        Self { steps: Vec::new() }
+    }
+
+    /// Steps `sys` with `update()` and records it.
+    pub fn update(&mut self, sys: &mut StateSpace<T, NU, NX, NY>) -> &mut Self {
+        sys.update();
+        self.record(sys)
+    }
+
+    /// Records `u`, `x`, and `y` of `sys`, for a system stepped some other way, for example with
+    /// `update_rk4()`.
+    pub fn record(&mut self, sys: &StateSpace<T, NU, NX, NY>) -> &mut Self {
+        self.steps.push(Step {
+            u: sys.u.get_val(),
+            x: sys.x.get_val(),
+            y: sys.y.get_val(),
+        });
+        self
+    }
+
+    /// Sets the value of `u` of `sys` to each recorded input in turn, steps it with `update()`,
+    /// and returns the new trajectory. The bounds of `u` are kept. `sys` should start from the
+    /// state the recording started from.
+    pub fn replay(&self, sys: &mut StateSpace<T, NU, NX, NY>) -> Self {
+        let mut run = Self::new();
+        for step in &self.steps {
+            sys.u.set_val_keep_held(step.u.clone());
+            run.update(sys);
+        }
+        run
+    }
+
+    /// The index of the first step where an element of `u`, `x`, or `y` differs from
+    /// `baseline` by more than `tol`, or `None` if every step matches. When the lengths differ,
+    /// the first step past the end of the shorter trajectory is a mismatch.
+    pub fn first_mismatch(&self, baseline: &Self, tol: T) -> Option<usize>
+    where
+        T: nalgebra::ClosedSubAssign,
+    {
+        let mismatch = self.steps.iter().zip(&baseline.steps).position(|(s, b)| {
+            !(within(&s.u, &b.u, &tol) && within(&s.x, &b.x, &tol) && within(&s.y, &b.y, &tol))
+        });
+        match mismatch {
+            Some(index) => Some(index),
+            None if self.len() != baseline.len() => Some(self.len().min(baseline.len())),
+            None => None,
+        }
+    }
+
+    /// True when the trajectory matches `baseline` within `tol`, see `first_mismatch()`.
+    pub fn matches(&self, baseline: &Self, tol: T) -> bool
+    where
+        T: nalgebra::ClosedSubAssign,
+    {
+        self.first_mismatch(baseline, tol).is_none()
+    }
+
+    /// The number of recorded steps.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+// True when every element of a and b differ by at most tol.
+fn within<T, const N: usize>(a: &SMatrix<T, N, 1>, b: &SMatrix<T, N, 1>, tol: &T) -> bool
+where
+    T: nalgebra::Scalar + nalgebra::ClosedSubAssign + PartialOrd,
+{
+    a.iter()
+        .zip(b.iter())
+        .all(|(a, b)| a.clone() - b.clone() <= *tol && b.clone() - a.clone() <= *tol)
+}
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> Default
+    for TrajectoryRecorder<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,261 @@
+/*!
+# reduction
+The `reduction` module reduces the number of states of a stable, minimal `StateSpace` system
+with balanced truncation, so a high order design can run on a target with a small budget.
+
+## Features.
+* `hankel_singular_values()` measures how much each state of the balanced realization
+  contributes to the input-output behavior.
+* `balance_and_truncate()` keeps the states with the largest Hankel singular values.
+* `minreal()` removes the uncontrollable and unobservable states, the minimal realization.
+
+In the balanced realization the controllability and observability Gramians are equal and
+diagonal, with the Hankel singular values `s_1 >= s_2 >= ... >= s_NX` on the diagonal. States with
+a small Hankel singular value are both hard to reach from `u` and hard to see in `y`, so they can
+be removed. Keeping `NR` states the error of the reduced system is bounded at every frequency by
+`|G(jw) - G_r(jw)| <= 2 * (s_(NR+1) + ... + s_NX)`. A clear gap in the Hankel singular values is a
+good place to truncate, when they decay slowly no small model is accurate.
+
+A state which is uncontrollable or unobservable, for example a pole cancelled by a zero, does not
+change the transfer function at all. `minreal()` removes these states exactly, without the error
+of a truncation, and works for unstable systems too. A loaded model should go through `minreal()`
+before `balance_and_truncate()`, which requires a minimal system.
+
+The Gramians and their factors are dynamically sized, so this module requires the `std` feature.
+*/
+
+use na::{DMatrix, DVector, RealField, SMatrix, SVector};
+use nalgebra as na;
+use num_traits::NumCast;
+
+use crate::{StateSpace, StateSpaceError};
+
+// The balancing transformation, its inverse, and the Hankel singular values.
+type Balancing<T> = (DMatrix<T>, DMatrix<T>, DVector<T>);
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: RealField + NumCast,
+{
+    /// The Hankel singular values, largest first, the square roots of the eigenvalues of the
+    /// product of the Gramians.
+    ///
+    /// Returns `StateSpaceError::NotStable` if the system is not stable, and
+    /// `StateSpaceError::NotMinimal` if it has states which are uncontrollable or unobservable.
+    ///
+    /// The first order system `3 / (s + 2)` has Gramians of `9 / 4` and `1 / 4`, so its Hankel
+    /// singular value is `sqrt(9 / 16) = 0.75`.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![-2.0]).set_b(matrix![3.0]).set_c(matrix![1.0]);
+    /// let hsv = sys.hankel_singular_values().unwrap();
+    /// assert!((hsv[0] - 0.75).abs() < 1e-12);
+    /// ```
+    pub fn hankel_singular_values(&self) -> Result<SVector<T, NX>, StateSpaceError> {
+        let (_, _, hsv) = self.balancing()?;
+        Ok(SVector::from_iterator(hsv.iter().cloned()))
+    }
+
+    /// Balanced truncation to `NR` states, the `NR` states of the balanced realization with the
+    /// largest Hankel singular values. `D`, `dt`, the system time, and `u` and `y` with their
+    /// bounds are copied from `self`, the state starts at zero.
+    ///
+    /// See the module documentation for the error bound. Returns an error if:
+    /// * `NR > NX`, `StateSpaceError::DimensionMismatch`.
+    /// * The system is not stable, `StateSpaceError::NotStable`.
+    /// * The system is not minimal, `StateSpaceError::NotMinimal`. Remove the uncontrollable or
+    ///   unobservable states first.
+    ///
+    /// This example is `1 / (s + 1) + 0.01 / (s + 10)`. The fast mode contributes very little,
+    /// so one state is enough and the DC gain is close to the original 1.001.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![-1.0, 0.0; 0.0, -10.0])
+    ///     .set_b(matrix![1.0; 0.1])
+    ///     .set_c(matrix![1.0, 0.1])
+    ///     .set_dt(0.01);
+    ///
+    /// let hsv = sys.hankel_singular_values().unwrap();
+    /// assert!(hsv[1] < 1e-2 * hsv[0]);
+    ///
+    /// let reduced: StateSpace<f64, 1, 1, 1> = sys.balance_and_truncate().unwrap();
+    /// let dc = sys.dc_gain().unwrap()[0];
+    /// let dc_reduced = reduced.dc_gain().unwrap()[0];
+    /// assert!((dc - 1.001).abs() < 1e-12);
+    /// assert!((dc - dc_reduced).abs() < 1e-3);
+    ///
+    /// // The error bound of the module documentation, which is reached at DC for this system.
+    /// assert!((dc - dc_reduced).abs() <= 2.0 * hsv[1] + 1e-12);
+    /// assert_eq!(0.01, reduced.get_dt());
+    /// ```
+    pub fn balance_and_truncate<const NR: usize>(
+        &self,
+    ) -> Result<StateSpace<T, NU, NR, NY>, StateSpaceError> {
+        if NR > NX {
+            return Err(StateSpaceError::DimensionMismatch);
+        }
+        let (t, t_inv, _) = self.balancing()?;
+
+        let a = DMatrix::from_iterator(NX, NX, self.a.iter().cloned());
+        let b = DMatrix::from_iterator(NX, NU, self.b.iter().cloned());
+        let c = DMatrix::from_iterator(NY, NX, self.c.iter().cloned());
+        let a_bal = &t_inv * a * &t;
+        let b_bal = t_inv * b;
+        let c_bal = c * t;
+
+        // The balanced states are sorted, so the first NR are kept.
+        let a_r = SMatrix::from_iterator(a_bal.view((0, 0), (NR, NR)).iter().cloned());
+        let b_r = SMatrix::from_iterator(b_bal.view((0, 0), (NR, NU)).iter().cloned());
+        let c_r = SMatrix::from_iterator(c_bal.view((0, 0), (NY, NR)).iter().cloned());
+
+        let mut sys = StateSpace::new();
+        sys.set_a(a_r)
+            .set_b(b_r)
+            .set_c(c_r)
+            .set_d(self.d.clone())
+            .set_u(self.u.clone())
+            .set_y(self.y.clone())
+            .set_dt(self.dt.clone());
+        sys.time = self.time;
+        Ok(sys)
+    }
+
+    /// The minimal realization with `NR` states, the controllable and observable part of the
+    /// Kalman decomposition. The controllable subspace is the range of the controllability
+    /// matrix and the observable part of it is the range of the transposed observability
+    /// matrix, with orthonormal bases from the SVD. Singular values smaller than
+    /// `tol * max_singular_value` are treated as zero, so a pole and zero which nearly cancel
+    /// are removed with a larger `tol`. `D`, `dt`, the system time, and `u` and `y` with their
+    /// bounds and names are copied from `self`, the state starts at zero.
+    ///
+    /// Returns `StateSpaceError::DimensionMismatch` if the minimal realization does not have
+    /// `NR` states.
+    ///
+    /// This example is `(s + 1) / ((s + 1)(s + 2))` in controllable canonical form. The zero
+    /// cancels the pole at -1, so the mode is unobservable and the minimal realization is
+    /// `1 / (s + 2)`.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, StateSpaceError};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; -2.0, -3.0])
+    ///     .set_b(matrix![0.0; 1.0])
+    ///     .set_c(matrix![1.0, 1.0])
+    ///     .set_dt(0.01);
+    /// assert!(!sys.is_observable());
+    ///
+    /// let min: StateSpace<f64, 1, 1, 1> = sys.minreal(1e-9).unwrap();
+    /// assert!((min.get_a()[0] + 2.0).abs() < 1e-12);
+    /// assert!((min.get_b()[0] * min.get_c()[0] - 1.0).abs() < 1e-12);
+    /// assert_eq!(0.01, min.get_dt());
+    ///
+    /// // The transfer function is unchanged.
+    /// let w = [0.0, 0.5, 2.0, 10.0];
+    /// let g = sys.freq_response(&w).unwrap();
+    /// let g_min = min.freq_response(&w).unwrap();
+    /// assert!(g.iter().zip(g_min.iter()).all(|(a, b)| (a - b).norm() < 1e-12));
+    ///
+    /// // The minimal realization has one state, not two.
+    /// let err = sys.minreal::<2>(1e-9).err();
+    /// assert_eq!(Some(StateSpaceError::DimensionMismatch), err);
+    /// ```
+    pub fn minreal<const NR: usize>(
+        &self,
+        tol: T,
+    ) -> Result<StateSpace<T, NU, NR, NY>, StateSpaceError> {
+        let a = DMatrix::from_iterator(NX, NX, self.a.iter().cloned());
+        let b = DMatrix::from_iterator(NX, NU, self.b.iter().cloned());
+        let c = DMatrix::from_iterator(NY, NX, self.c.iter().cloned());
+
+        // The controllable part, x = Uc xc.
+        let u_c = range_basis(self.controllability_matrix(), tol.clone());
+        let a_c = u_c.transpose() * a * &u_c;
+        let b_c = u_c.transpose() * b;
+        let c_c = c * &u_c;
+
+        // The observable part of it, xc = Vo xm, from the observability matrix of (Ac, Cc).
+        let nc = u_c.ncols();
+        let mut obsv = DMatrix::zeros(nc * NY, nc);
+        let mut c_a_k = c_c.clone();
+        for k in 0..nc {
+            obsv.view_mut((k * NY, 0), (NY, nc)).copy_from(&c_a_k);
+            c_a_k *= &a_c;
+        }
+        let v_o = range_basis(obsv.transpose(), tol);
+        if v_o.ncols() != NR {
+            return Err(StateSpaceError::DimensionMismatch);
+        }
+
+        let mut sys = StateSpace::new();
+        sys.set_a(SMatrix::from_iterator(
+            (v_o.transpose() * a_c * &v_o).iter().cloned(),
+        ))
+        .set_b(SMatrix::from_iterator(
+            (v_o.transpose() * b_c).iter().cloned(),
+        ))
+        .set_c(SMatrix::from_iterator((c_c * v_o).iter().cloned()))
+        .set_d(self.d.clone())
+        .set_u(self.u.clone())
+        .set_y(self.y.clone())
+        .set_dt(self.dt.clone());
+        sys.time = self.time;
+        sys.input_names = self.input_names;
+        sys.output_names = self.output_names;
+        Ok(sys)
+    }
+
+    // The balancing transformation with the square root method. With the Cholesky factors
+    // Wc = Lc Lc^T, Wo = Lo Lo^T and the SVD Lo^T Lc = U S V^T, the balanced state is
+    // x_bal = T^{-1} x with T = Lc V S^{-1/2} and T^{-1} = S^{-1/2} U^T Lo^T. Returns
+    // (T, T^{-1}, S), with the Hankel singular values S sorted largest first.
+    fn balancing(&self) -> Result<Balancing<T>, StateSpaceError> {
+        let wc = self.controllability_gramian()?;
+        let wo = self.observability_gramian()?;
+        let lc = DMatrix::from_iterator(NX, NX, wc.iter().cloned())
+            .cholesky()
+            .ok_or(StateSpaceError::NotMinimal)?
+            .unpack();
+        let lo = DMatrix::from_iterator(NX, NX, wo.iter().cloned())
+            .cholesky()
+            .ok_or(StateSpaceError::NotMinimal)?
+            .unpack();
+
+        let svd = (lo.transpose() * &lc).svd(true, true);
+        let hsv = svd.singular_values;
+        if hsv.iter().any(|s| *s <= T::zero()) {
+            return Err(StateSpaceError::NotMinimal);
+        }
+        let u = svd.u.expect("SVD requested U");
+        let v_t = svd.v_t.expect("SVD requested V^T");
+        let s_inv_sqrt = DMatrix::from_diagonal(&hsv.map(|s| T::one() / s.sqrt()));
+
+        let t = lc * v_t.transpose() * &s_inv_sqrt;
+        let t_inv = s_inv_sqrt * u.transpose() * lo.transpose();
+        Ok((t, t_inv, hsv))
+    }
+}
+
+// An orthonormal basis of the range of m, the left singular vectors with a singular value larger
+// than tol * max_singular_value.
+fn range_basis<T: RealField>(m: DMatrix<T>, tol: T) -> DMatrix<T> {
+    if m.is_empty() {
+        return DMatrix::zeros(m.nrows(), 0);
+    }
+    let svd = m.svd(true, false);
+    let u = svd.u.expect("SVD requested U");
+    let cutoff = tol * svd.singular_values.max();
+    let cols: Vec<usize> = (0..svd.singular_values.len())
+        .filter(|i| svd.singular_values[*i] > cutoff && svd.singular_values[*i] > T::zero())
+        .collect();
+    u.select_columns(cols.iter())
+}
@@ -0,0 +1,150 @@
+/*!
+# schedule
+The `schedule` module contains `GainScheduler`, which blends several `StateSpace` models of a
+plant or controller designed at different operating points.
+
+## Features.
+* Each model is keyed by a value of the scheduling variable, for example the airspeed or the load.
+* `step()` interpolates the matrices of the two models around the current scheduling variable,
+  linearly, and steps one system with them. The state is carried from step to step, so the
+  output stays continuous as the operating point moves.
+* Outside the range of the keys the first or last model is used.
+
+The models must share the meaning of their states, since one state vector is stepped with the
+blended matrices. Models from the same design procedure at different operating points usually
+do, while a `transform()` of one of them would not.
+*/
+
+use na::{RealField, SMatrix};
+use nalgebra as na;
+use num_traits::NumCast;
+
+use crate::{StateSpace, StateSpaceError};
+
+/// Blends `NM` models keyed by a scalar scheduling variable. The bounds, rate limit, `dt`, and
+/// initial state of the stepped system are those of the first model.
+///
+/// This example blends the lags `1 / (s + 1)` and `3 / (s + 1)`, designed at the operating
+/// points 0 and 1. Half way between them the DC gain is 2.
+///
+/// ```rust
+/// use nalgebra::{matrix, vector};
+/// use state_space::{GainScheduler, StateSpace, StateSpaceError};
+///
+/// let mut low: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+/// low.set_a(matrix![-1.0]).set_b(matrix![1.0]).set_c(matrix![1.0]).set_dt(0.01);
+/// let mut high = low;
+/// high.set_b(matrix![3.0]);
+///
+/// let mut scheduler = GainScheduler::new([0.0, 1.0], [low, high]).unwrap();
+/// let mut y = vector![0.0];
+/// for _ in 0..2000 {
+///     y = scheduler.step(0.5, vector![1.0]);
+/// }
+/// assert!((y[0] - 2.0).abs() < 1e-6);
+/// assert_eq!(matrix![2.0], scheduler.get_active().get_b());
+///
+/// // Outside the keys the last model is used, the state carries over.
+/// for _ in 0..2000 {
+///     y = scheduler.step(5.0, vector![1.0]);
+/// }
+/// assert!((y[0] - 3.0).abs() < 1e-6);
+///
+/// let err = GainScheduler::new([1.0, 0.0], [low, high]).err();
+/// assert_eq!(Some(StateSpaceError::InvalidSchedule), err);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct GainScheduler<T, const NM: usize, const NU: usize, const NX: usize, const NY: usize> {
+    keys: [T; NM],
+    models: [StateSpace<T, NU, NX, NY>; NM],
+    active: StateSpace<T, NU, NX, NY>,
+}
+
+// The linear interpolation m0 + w (m1 - m0), as m0 (1 - w) + m1 w.
+fn lerp<T: RealField, const R: usize, const C: usize>(
+    m0: &SMatrix<T, R, C>,
+    m1: &SMatrix<T, R, C>,
+    w: T,
+) -> SMatrix<T, R, C> {
+    m0 * (T::one() - w.clone()) + m1 * w
+}
+
+impl<T, const NM: usize, const NU: usize, const NX: usize, const NY: usize>
+    GainScheduler<T, NM, NU, NX, NY>
+where
+    T: RealField + NumCast,
+{
+    /// Create a scheduler from the keys, the values of the scheduling variable, and a model for
+    /// each key.
+    ///
+    /// Returns an error if:
+    /// * There are no models, `StateSpaceError::DimensionMismatch`.
+    /// * The keys are not strictly increasing, `StateSpaceError::InvalidSchedule`.
+    /// * The models do not all have the same `dt`, `StateSpaceError::DtMismatch`.
+    /// * The models are not all continuous or all discrete, `StateSpaceError::SystemTimeMismatch`.
+    pub fn new(
+        keys: [T; NM],
+        models: [StateSpace<T, NU, NX, NY>; NM],
+    ) -> Result<Self, StateSpaceError> {
+        let active = models
+            .first()
+            .cloned()
+            .ok_or(StateSpaceError::DimensionMismatch)?;
+        if keys.windows(2).any(|k| k[0] >= k[1]) {
+            return Err(StateSpaceError::InvalidSchedule);
+        }
+        for model in models.iter() {
+            if model.dt != active.dt {
+                return Err(StateSpaceError::DtMismatch);
+            }
+            if model.time != active.time {
+                return Err(StateSpaceError::SystemTimeMismatch);
+            }
+        }
+        Ok(Self {
+            keys,
+            models,
+            active,
+        })
+    }
+
+    /// Blends the models at the scheduling variable `s`, sets the input `u`, and steps the
+    /// system with `update()`. Returns the output, see `StateSpace::step()`.
+    pub fn step(&mut self, s: T, u: SMatrix<T, NU, 1>) -> SMatrix<T, NY, 1> {
+        self.blend(s);
+        self.active.step(u)
+    }
+
+    /// The system stepped by `step()`, with the matrices of the last blend.
+    pub fn get_active(&self) -> &StateSpace<T, NU, NX, NY> {
+        &self.active
+    }
+
+    /// Mutable reference to the system stepped by `step()`, for example to set its state or
+    /// bounds. The matrices are replaced by the next `step()`.
+    pub fn get_active_mut(&mut self) -> &mut StateSpace<T, NU, NX, NY> {
+        &mut self.active
+    }
+
+    // Sets the matrices of the active system to the linear interpolation of the two models
+    // around s, or to the first or last model outside the keys.
+    fn blend(&mut self, s: T) {
+        let i = self.keys.iter().rposition(|k| *k <= s).unwrap_or(0);
+        if i + 1 >= NM || s <= self.keys[0] {
+            let model = &self.models[i];
+            self.active
+                .set_a(model.a.clone())
+                .set_b(model.b.clone())
+                .set_c(model.c.clone())
+                .set_d(model.d.clone());
+            return;
+        }
+        let w = (s - self.keys[i].clone()) / (self.keys[i + 1].clone() - self.keys[i].clone());
+        let (m0, m1) = (&self.models[i], &self.models[i + 1]);
+        self.active
+            .set_a(lerp(&m0.a, &m1.a, w.clone()))
+            .set_b(lerp(&m0.b, &m1.b, w.clone()))
+            .set_c(lerp(&m0.c, &m1.c, w.clone()))
+            .set_d(lerp(&m0.d, &m1.d, w));
+    }
+}
@@ -0,0 +1,91 @@
+/*!
+# serialize
+The `serialize` module implements `Serialize` and `Deserialize` for `StateSpace` and `SysVec` with
+the optional `serde` feature. The fields are serialized by a derive, and a loaded system is checked
+with the setters, so it keeps the same invariants as a system built in code.
+
+## Features.
+* `dt` must be positive, as for `try_set_dt()`.
+* The lower bound of each `SysVec` must not be above its upper bound, as for `from_vectors()`.
+*/
+
+use num_traits::{NumCast, One, Zero};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{StateSpace, StateSpaceError, SysVec};
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> Serialize for StateSpace<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // The inherent serialize() of the derive with #[serde(remote = "Self")].
+        StateSpace::serialize(self, serializer)
+    }
+}
+
+impl<'de, T, const NU: usize, const NX: usize, const NY: usize> Deserialize<'de>
+    for StateSpace<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast
+        + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // The inherent deserialize() of the derive with #[serde(remote = "Self")], which reads
+        // the fields unchecked.
+        let sys: Self = StateSpace::deserialize(deserializer)?;
+        sys.checked().map_err(D::Error::custom)
+    }
+}
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    // Passes the fields of a loaded system through their setters, and returns the error of
+    // the first setter which rejects one.
+    fn checked(mut self) -> Result<Self, StateSpaceError> {
+        self.try_set_dt(self.dt.clone())?;
+        Ok(self)
+    }
+}
+
+impl<T, const N: usize> Serialize for SysVec<T, N>
+where
+    T: nalgebra::Scalar + Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SysVec::serialize(self, serializer)
+    }
+}
+
+impl<'de, T, const N: usize> Deserialize<'de> for SysVec<T, N>
+where
+    T: nalgebra::Scalar + PartialOrd + NumCast + Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let loaded: Self = SysVec::deserialize(deserializer)?;
+        let mut vec = SysVec::from_vectors(loaded.get_val(), loaded.get_lb(), loaded.get_ub())
+            .map_err(D::Error::custom)?;
+        if let Some(rate) = loaded.get_rate_limit() {
+            vec.set_rate_limit(rate);
+        }
+        if let Some(delta) = loaded.get_clamp_hysteresis() {
+            vec.set_clamp_hysteresis(delta);
+        }
+        Ok(vec)
+    }
+}
@@ -0,0 +1,288 @@
+/*!
+# simulate
+The `simulate` module contains convenience methods which step a `StateSpace` system many times and
+collect the results, so a simulation does not need to be wired up with a manual loop.
+
+## Features.
+* `simulate()` applies a sequence of inputs and returns the sequence of outputs.
+* `step_response()` returns the response to a unit step from a zero state.
+* `impulse_response()` returns the response to a unit impulse from a zero state.
+* `simulate_timeseries()` plays back an input recorded at its own timestamps, interpolated to
+  `dt`.
+
+The results are collected in a `Vec`, so this module requires the `std` feature.
+*/
+
+use core::cmp::Ordering;
+
+use na::{SMatrix, SVector};
+use nalgebra as na;
+use num_traits::{NumCast, One, Zero};
+
+use crate::{StateSpace, StateSpaceError, SysVec};
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast,
+{
+    /// Applies each input of `u_seq` with `set_u()` followed by one `update()`, and returns the
+    /// output after each step. The simulation starts from the current state, so a sequence can
+    /// be continued with another call.
+    ///
+    /// This is the same simulation as `main.rs`, a first order system stepped 50 times.
+    ///
+    /// ```rust
+    /// use nalgebra::SMatrix;
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(-1.0 * SMatrix::identity())
+    ///     .set_b(SMatrix::identity())
+    ///     .set_c(SMatrix::identity())
+    ///     .set_x(SysVec::from_val(0.1))
+    ///     .set_dt(0.1);
+    /// let mut manual = sys;
+    ///
+    /// let u_seq = [SysVec::from_scalars(1.0, -9e99, 9e99); 50];
+    /// let y_seq = sys.simulate(&u_seq);
+    ///
+    /// manual.set_u(u_seq[0]);
+    /// for _ in 0..50 {
+    ///     manual.update();
+    /// }
+    ///
+    /// assert_eq!(50, y_seq.len());
+    /// assert_eq!(manual.get_y(), y_seq[49].get_val());
+    /// assert_eq!(manual.get_x(), sys.get_x());
+    /// ```
+    pub fn simulate(&mut self, u_seq: &[SysVec<T, NU>]) -> Vec<SysVec<T, NY>> {
+        u_seq
+            .iter()
+            .map(|u| {
+                self.set_u(u.clone()).update();
+                self.y.clone()
+            })
+            .collect()
+    }
+
+    /// Unit step response from a zero state. The state is reset with `reset()`, `u` is set to
+    /// ones, and the output after each of `n_steps` calls to `update()` is returned. The bounds
+    /// of `u`, `x`, and `y` are kept, and `u` is left at the step value afterwards. The input
+    /// applied before the first step is reset to zero too, so a rate limit ramps up from zero.
+    ///
+    /// With `channel = None` all inputs are stepped together. `Some(i)` steps only input `i` of a
+    /// MIMO system, the other inputs are held at zero.
+    ///
+    /// Panics if `channel` is `Some(i)` with `i >= NU`.
+    ///
+    /// This is the second order system of Example 2, which overshoots before it settles at 1.0.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::StateSpace;
+    ///
+    /// let w = 2.0 * std::f64::consts::PI;
+    /// let z = 0.707_f64;
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; -w * w, -2.0 * z * w])
+    ///     .set_b(matrix![0.0; w * w])
+    ///     .set_c(matrix![1.0, 0.0])
+    ///     .set_dt(0.01);
+    ///
+    /// let y: Vec<f64> = sys.step_response(300, None).iter().map(|y| y[0]).collect();
+    /// let peak = y.iter().cloned().fold(f64::MIN, f64::max);
+    /// assert!(peak > 1.0);
+    /// assert!((y[299] - 1.0).abs() < 1e-3);
+    ///
+    /// // With a rate limit the input ramps up from zero, also on a second run.
+    /// sys.get_u_mut().set_rate_limit(matrix![10.0]);
+    /// let ramped = sys.step_response(300, None);
+    /// assert!(ramped[10][0] < y[10]);
+    /// assert_eq!(ramped, sys.step_response(300, None));
+    /// ```
+    pub fn step_response(
+        &mut self,
+        n_steps: usize,
+        channel: Option<usize>,
+    ) -> Vec<SMatrix<T, NY, 1>> {
+        let mut u: SMatrix<T, NU, 1> = SMatrix::zeros();
+        match channel {
+            Some(i) => {
+                assert!(
+                    i < NU,
+                    "step_response() channel {} is out of range, NU = {}",
+                    i,
+                    NU
+                );
+                u[i] = T::one();
+            }
+            None => u.fill(T::one()),
+        }
+
+        self.reset();
+        self.u_applied = SMatrix::zeros();
+        self.u.set_val(u);
+        (0..n_steps).map(|_| self.update().get_y()).collect()
+    }
+
+    /// Unit impulse response from a zero state. The state and the input applied before the
+    /// first step are reset, and the output after each of `n_steps` calls to `update()` is
+    /// returned.
+    ///
+    /// The impulse is approximated by a pulse of one sample with a unit area, so `u = 1/dt` for
+    /// the first step and `u = 0` afterwards. This is the same convention as MATLAB's `impulse`.
+    /// The input bounds still apply, so they must allow `1/dt`. As for `step_response()`,
+    /// `channel` selects a single input, or all inputs with `None`.
+    ///
+    /// Panics if `channel` is `Some(i)` with `i >= NU`.
+    ///
+    /// The integral of the impulse response is the step response.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; -4.0, -2.0])
+    ///     .set_b(matrix![0.0; 4.0])
+    ///     .set_c(matrix![1.0, 0.0])
+    ///     .set_dt(0.05);
+    ///
+    /// let impulse = sys.impulse_response(100, None);
+    /// let step = sys.step_response(100, None);
+    ///
+    /// let mut integral = 0.0;
+    /// for k in 0..100 {
+    ///     integral += impulse[k][0] * 0.05;
+    ///     assert!((integral - step[k][0]).abs() < 1e-12);
+    /// }
+    /// ```
+    pub fn impulse_response(
+        &mut self,
+        n_steps: usize,
+        channel: Option<usize>,
+    ) -> Vec<SMatrix<T, NY, 1>>
+    where
+        T: nalgebra::ClosedDivAssign,
+    {
+        let pulse = T::one() / self.dt.clone();
+        let mut u: SMatrix<T, NU, 1> = SMatrix::zeros();
+        match channel {
+            Some(i) => {
+                assert!(
+                    i < NU,
+                    "impulse_response() channel {} is out of range, NU = {}",
+                    i,
+                    NU
+                );
+                u[i] = pulse;
+            }
+            None => u.fill(pulse),
+        }
+
+        self.reset();
+        self.u_applied = SMatrix::zeros();
+        self.u.set_val(u);
+        let mut y = Vec::with_capacity(n_steps);
+        for _ in 0..n_steps {
+            y.push(self.update().get_y());
+            self.u.set_val(SMatrix::zeros());
+        }
+        y
+    }
+
+    /// Plays back an input sampled at `times`, for example recorded flight data, and returns the
+    /// output after each step. The system is stepped from `t = 0` at its own `dt` while
+    /// `t < t_end`, starting from the current state. At each step `u` is linearly interpolated
+    /// between the two neighboring samples of `inputs`. Before the first timestamp the first
+    /// sample is held, and after the last timestamp the last sample is held.
+    ///
+    /// Returns an error if:
+    /// * `times` is empty or does not have the same length as `inputs`,
+    ///   `StateSpaceError::DimensionMismatch`. Recorded data is easily cut short.
+    /// * `times` is not strictly increasing, or `t_end` is negative or not finite,
+    ///   `StateSpaceError::InvalidTimes`.
+    ///
+    /// This example uses `y = u` to show the input applied at each step. The ramp from 0 to 10
+    /// over one second is interpolated at a `dt` of 0.25, then held at 10.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, vector};
+    /// use state_space::{StateSpace, StateSpaceError};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_d(matrix![1.0]).set_dt(0.25);
+    ///
+    /// let y = sys.simulate_timeseries(&[0.0, 1.0], &[vector![0.0], vector![10.0]], 2.0);
+    /// let y: Vec<f64> = y.unwrap().iter().map(|y| y.get_val()[0]).collect();
+    /// assert_eq!(vec![0.0, 2.5, 5.0, 7.5, 10.0, 10.0, 10.0, 10.0], y);
+    ///
+    /// let err = sys.simulate_timeseries(&[0.0, 1.0], &[vector![0.0]], 2.0).err();
+    /// assert_eq!(Some(StateSpaceError::DimensionMismatch), err);
+    /// let err = sys.simulate_timeseries(&[], &[], 2.0).err();
+    /// assert_eq!(Some(StateSpaceError::DimensionMismatch), err);
+    ///
+    /// let err = sys.simulate_timeseries(&[1.0, 0.0], &[vector![0.0], vector![10.0]], 2.0).err();
+    /// assert_eq!(Some(StateSpaceError::InvalidTimes), err);
+    /// let err = sys.simulate_timeseries(&[0.0, 1.0], &[vector![0.0], vector![10.0]], f64::NAN);
+    /// assert_eq!(Some(StateSpaceError::InvalidTimes), err.err());
+    /// ```
+    pub fn simulate_timeseries(
+        &mut self,
+        times: &[T],
+        inputs: &[SVector<T, NU>],
+        t_end: T,
+    ) -> Result<Vec<SysVec<T, NY>>, StateSpaceError>
+    where
+        T: nalgebra::ClosedDivAssign + nalgebra::ClosedSubAssign,
+    {
+        if times.is_empty() || times.len() != inputs.len() {
+            return Err(StateSpaceError::DimensionMismatch);
+        }
+        if times
+            .windows(2)
+            .any(|w| w[0].partial_cmp(&w[1]) != Some(Ordering::Less))
+        {
+            return Err(StateSpaceError::InvalidTimes);
+        }
+        let end = t_end.to_f64().ok_or(StateSpaceError::ConversionFailed)?;
+        let dt = self.dt.to_f64().ok_or(StateSpaceError::ConversionFailed)?;
+        if !end.is_finite() || end < 0.0 {
+            return Err(StateSpaceError::InvalidTimes);
+        }
+        let steps = (end / dt).ceil() as usize;
+        let last = times.len() - 1;
+
+        let mut y = Vec::new();
+        let mut i = 0;
+        for k in 0..steps {
+            let t = self.dt.clone() * T::from(k).expect("Conversion failed");
+            if t >= t_end {
+                break;
+            }
+
+            // Advance to the sample interval [times[i], times[i + 1]) which contains t.
+            while i < last && times[i + 1] <= t {
+                i += 1;
+            }
+            let u = if t <= times[0] {
+                inputs[0].clone()
+            } else if i == last {
+                inputs[last].clone()
+            } else {
+                let frac = (t - times[i].clone()) / (times[i + 1].clone() - times[i].clone());
+                inputs[i].clone() + (inputs[i + 1].clone() - inputs[i].clone()) * frac
+            };
+
+            self.u.set_val(u);
+            y.push(self.update().y.clone());
+        }
+        Ok(y)
+    }
+}
@@ -9,6 +9,8 @@ associated with `NU`, `NX`, or `NY`.
     * .val holds the vector.
     * .lb is the lower bound, default value is -9e99.
     * .ub is the upper bound, default value is +9e99.
+    * .rate is the optional rate limit, default is no limit. It is only applied to the input `u`.
+    * .hysteresis is the optional clamp hysteresis band, default is none.
 * Several constructors, setters, and similar methods.
     * See the examples below.
 */
@@ -17,19 +19,50 @@ associated with `NU`, `NX`, or `NY`.
 use na::SVector;
 use nalgebra as na;
 
+use crate::StateSpaceError;
+
 // A struct to hold a system vector and it's limits.
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        remote = "Self",
+        bound(
+            serialize = "T: nalgebra::Scalar + serde::Serialize",
+            deserialize = "T: nalgebra::Scalar + serde::Deserialize<'de>"
+        )
+    )
+)]
 pub struct SysVec<T, const N: usize> {
     val: SVector<T, N>,
     lb: SVector<T, N>,
     ub: SVector<T, N>,
+    rate: Option<SVector<T, N>>,
+    hysteresis: Option<SVector<T, N>>,
+    #[cfg_attr(feature = "serde", serde(skip, default = "all_free"))]
+    held: [Held; N],
+}
+
+// Which bound, if any, an element is held at by the clamp hysteresis.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Held {
+    Free,
+    Lower,
+    Upper,
+}
+
+// The held state of a deserialized SysVec, which starts free as for a new one.
+#[cfg(feature = "serde")]
+fn all_free<const N: usize>() -> [Held; N] {
+    [Held::Free; N]
 }
 
 // DEBUG, remove all println, replace with assert_eq or similar.
 
 impl<T, const N: usize> SysVec<T, N>
 where
-    T: nalgebra::Scalar + PartialOrd + num_traits::NumCast,
+    T: nalgebra::Scalar + num_traits::NumCast,
 {
 
     /// Construct a SysVec struct with default values: 0 for val and +/-9e99 for ub and lb.
@@ -45,22 +78,21 @@ where
     /// assert_eq!(exp_val, my_vec.get_val())
     /// ```
     pub fn new() -> Self {
-        let zero = T::from(0).unwrap();
-        let val_vec: SVector<T, N> = SVector::from_element(zero);
-
-        let min_value: T = T::from(-9e99_f64).expect("Conversion failed");
-        let lb_vec: SVector<T, N> = SVector::from_element(min_value);
-
-        let max_value: T = T::from(9e99_f64).expect("Conversion failed");
-        let ub_vec: SVector<T, N> = SVector::from_element(max_value);
-
-        Self {
-            val: val_vec,
-            lb: lb_vec,
-            ub: ub_vec,
-        }
+        Self::try_new().expect("Conversion failed")
     }
 
+    /// Fallible version of `SysVec::new()`. Returns `StateSpaceError::ConversionFailed` if the
+    /// default bounds of +/-9e99 can not be represented by `T`, for example an integer type.
+    ///
+    /// ```rust
+    /// use state_space::{StateSpaceError, SysVec};
+    ///
+    /// assert!(SysVec::<f64, 3>::try_new().is_ok());
+    /// assert_eq!(Some(StateSpaceError::ConversionFailed), SysVec::<i32, 3>::try_new().err());
+    /// ```
+    pub fn try_new() -> Result<Self, StateSpaceError> {
+        Self::try_from_scalars(0.0, -9e99, 9e99)
+    }
 
     /// Construct a SysVec struct from a scalar value. All entries in the value matrix will be
     /// the same. The default values for lb and ub are applied.
@@ -77,10 +109,27 @@ where
     /// println!("my_vec = {:?}", my_vec);
     /// ```
     pub fn from_val(val: f64) -> Self {
-        let val_value: T = T::from(val).expect("Conversion failed");
+        Self::try_from_val(val).expect("Conversion failed")
+    }
+
+    /// Fallible version of `SysVec::from_val()`. Returns `StateSpaceError::ConversionFailed` if
+    /// `val` or the default bounds can not be represented by `T`.
+    ///
+    /// ```rust
+    /// use state_space::{StateSpaceError, SysVec};
+    ///
+    /// let my_vec = SysVec::<f32, 3>::try_from_val(10.17).unwrap();
+    /// assert_eq!(10.17, my_vec.get_val()[0]);
+    ///
+    /// // The default bounds of +/-9e99 do not fit in an i32, use try_from_scalars() instead.
+    /// let result = SysVec::<i32, 3>::try_from_val(1.0);
+    /// assert_eq!(Some(StateSpaceError::ConversionFailed), result.err());
+    /// ```
+    pub fn try_from_val(val: f64) -> Result<Self, StateSpaceError> {
+        let val_value: T = T::from(val).ok_or(StateSpaceError::ConversionFailed)?;
         let val_vec: SVector<T, N> = SVector::from_element(val_value);
 
-        Self::new().set_val(val_vec).clone()
+        Ok(Self::try_new()?.set_val(val_vec).clone())
     }
 
     /// Construct a SysVec struct from scalar entries for val, lb, and ub. The scalar entries are
@@ -103,20 +152,43 @@ where
     /// assert_eq!(exp_ub, my_vec.get_ub());
     /// ```
     pub fn from_scalars(val: f64, lb: f64, ub: f64) -> Self {
-        let val_value: T = T::from(val).expect("Conversion failed");
+        Self::try_from_scalars(val, lb, ub).expect("Conversion failed")
+    }
+
+    /// Fallible version of `SysVec::from_scalars()`. Returns `StateSpaceError::ConversionFailed`
+    /// if `val`, `lb`, or `ub` can not be represented by `T`.
+    ///
+    /// ```rust
+    /// use state_space::{StateSpaceError, SysVec};
+    /// type T = i32;
+    /// const N: usize = 3;
+    ///
+    /// // Values that fit in an i32 are fine.
+    /// let my_vec = SysVec::<T, N>::try_from_scalars(7.0, -10.0, 10.0).unwrap();
+    /// assert_eq!(7, my_vec.get_val()[0]);
+    ///
+    /// // 1e12 overflows an i32.
+    /// let result = SysVec::<T, N>::try_from_scalars(1e12, -10.0, 10.0);
+    /// assert_eq!(Some(StateSpaceError::ConversionFailed), result.err());
+    /// ```
+    pub fn try_from_scalars(val: f64, lb: f64, ub: f64) -> Result<Self, StateSpaceError> {
+        let val_value: T = T::from(val).ok_or(StateSpaceError::ConversionFailed)?;
         let val_vec: SVector<T, N> = SVector::from_element(val_value);
 
-        let min_value: T = T::from(lb).expect("Conversion failed");
+        let min_value: T = T::from(lb).ok_or(StateSpaceError::ConversionFailed)?;
         let lb_vec: SVector<T, N> = SVector::from_element(min_value);
 
-        let max_value: T = T::from(ub).expect("Conversion failed");
+        let max_value: T = T::from(ub).ok_or(StateSpaceError::ConversionFailed)?;
         let ub_vec: SVector<T, N> = SVector::from_element(max_value);
 
-        Self::new()
-            .set_val(val_vec)
-            .set_lb(lb_vec)
-            .set_ub(ub_vec)
-            .clone()
+        Ok(Self {
+            val: val_vec,
+            lb: lb_vec,
+            ub: ub_vec,
+            rate: None,
+            hysteresis: None,
+            held: [Held::Free; N],
+        })
     }
 
     /// Setter for SysVec.val property. The input is an SVector.
@@ -136,6 +208,14 @@ where
     /// assert_eq!(exp_val, my_vec.get_val());
     /// ```
     pub fn set_val(&mut self, val: SVector<T, N>) -> &mut SysVec<T, { N }> {
+        self.val = val;
+        self.held = [Held::Free; N];
+        self
+    }
+
+    // Used by StateSpace for the inputs it writes each step, see step(). Sets val like
+    // set_val(), but keeps the elements held at a bound by the clamp hysteresis.
+    pub(crate) fn set_val_keep_held(&mut self, val: SVector<T, N>) -> &mut Self {
         self.val = val;
         self
     }
@@ -158,6 +238,7 @@ where
     /// ```
     pub fn set_lb(&mut self, lb: SVector<T, N>) -> &mut Self {
         self.lb = lb;
+        self.held = [Held::Free; N];
         self
     }
 
@@ -179,9 +260,166 @@ where
     /// ```
     pub fn set_ub(&mut self, ub: SVector<T, N>) -> &mut Self {
         self.ub = ub;
+        self.held = [Held::Free; N];
+        self
+    }
+
+    /// Setter for a single element of SysVec.lb. The other elements are unchanged. Returns
+    /// `StateSpaceError::IndexOutOfRange` if `index >= N`.
+    ///
+    /// ```rust
+    /// use nalgebra::vector;
+    /// use state_space::{StateSpaceError, SysVec};
+    /// type T = f64;
+    /// const N: usize = 3;
+    ///
+    /// let mut my_vec: SysVec<T, N> = SysVec::new();
+    /// my_vec.set_lb_at(0, -1.0).unwrap();
+    /// assert_eq!(vector![-1.0, -9e99, -9e99], my_vec.get_lb());
+    ///
+    /// assert_eq!(Some(StateSpaceError::IndexOutOfRange), my_vec.set_lb_at(3, -1.0).err());
+    /// ```
+    pub fn set_lb_at(&mut self, index: usize, value: T) -> Result<&mut Self, StateSpaceError> {
+        *self
+            .lb
+            .get_mut(index)
+            .ok_or(StateSpaceError::IndexOutOfRange)? = value;
+        self.held[index] = Held::Free;
+        Ok(self)
+    }
+
+    /// Setter for a single element of SysVec.ub. The other elements are unchanged. Returns
+    /// `StateSpaceError::IndexOutOfRange` if `index >= N`.
+    ///
+    /// ```rust
+    /// use nalgebra::vector;
+    /// use state_space::{StateSpaceError, SysVec};
+    /// type T = f64;
+    /// const N: usize = 3;
+    ///
+    /// let mut my_vec: SysVec<T, N> = SysVec::new();
+    /// my_vec.set_ub_at(1, 2.5).unwrap();
+    /// assert_eq!(vector![9e99, 2.5, 9e99], my_vec.get_ub());
+    ///
+    /// assert_eq!(Some(StateSpaceError::IndexOutOfRange), my_vec.set_ub_at(3, 2.5).err());
+    /// ```
+    pub fn set_ub_at(&mut self, index: usize, value: T) -> Result<&mut Self, StateSpaceError> {
+        *self
+            .ub
+            .get_mut(index)
+            .ok_or(StateSpaceError::IndexOutOfRange)? = value;
+        self.held[index] = Held::Free;
+        Ok(self)
+    }
+
+    /// Setter for a single element of SysVec.val. The other elements and the bounds are
+    /// unchanged, and the value is not clamped until `clamp()` or `update()` is called. Returns
+    /// `StateSpaceError::IndexOutOfRange` if `index >= N`.
+    ///
+    /// ```rust
+    /// use nalgebra::vector;
+    /// use state_space::{StateSpaceError, SysVec};
+    /// type T = f64;
+    /// const N: usize = 3;
+    ///
+    /// let mut my_vec: SysVec<T, N> = SysVec::from_scalars(0.0, -1.0, 1.0);
+    /// my_vec.set(2, 5.0).unwrap();
+    /// assert_eq!(vector![0.0, 0.0, 5.0], my_vec.get_val());
+    /// assert_eq!(vector![-1.0, -1.0, -1.0], my_vec.get_lb());
+    /// assert_eq!(vector![1.0, 1.0, 1.0], my_vec.get_ub());
+    ///
+    /// assert_eq!(Some(StateSpaceError::IndexOutOfRange), my_vec.set(3, 5.0).err());
+    /// ```
+    pub fn set(&mut self, index: usize, value: T) -> Result<&mut Self, StateSpaceError> {
+        *self
+            .val
+            .get_mut(index)
+            .ok_or(StateSpaceError::IndexOutOfRange)? = value;
+        self.held[index] = Held::Free;
+        Ok(self)
+    }
+
+    /// Setter for SysVec.rate property, the maximum rate of change per time unit of each
+    /// element. The input is an SVector. By default there is no rate limit. The rate limit is
+    /// applied by `StateSpace::update()` to the input `u`, see `StateSpace::set_u()`.
+    ///
+    /// ```rust
+    /// use nalgebra::SVector;
+    /// use state_space::SysVec;
+    /// type T = f64;
+    /// const N: usize = 3;
+    ///
+    /// let mut my_vec: SysVec<T, N> = SysVec::new();
+    ///
+    /// let new_rate: SVector<T, N> = SVector::from_element(0.5);
+    /// my_vec.set_rate_limit(new_rate);
+    ///
+    /// let exp_rate: SVector<T, N> = SVector::from_element(0.5);
+    /// assert_eq!(Some(exp_rate), my_vec.get_rate_limit());
+    /// ```
+    pub fn set_rate_limit(&mut self, rate: SVector<T, N>) -> &mut Self {
+        self.rate = Some(rate);
+        self
+    }
+
+    /// Setter for SysVec.hysteresis property, a band inside each bound which stops an element
+    /// chattering on and off the bound. Once `clamp()` holds an element at a bound, it stays at
+    /// the bound until the value moves more than `delta` inside it. By default there is no
+    /// hysteresis. `set_val()`, `set()`, and the setters of the bounds release every held
+    /// element.
+    ///
+    /// `StateSpace::update()` applies the band to `u` and `y`, which are found anew each step.
+    /// The state `x` is stepped from its clamped value, so a state held at a bound would never
+    /// move more than the band in one step, and `x` is clamped without the band.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, vector, SVector};
+    /// use state_space::{StateSpace, SysVec};
+    /// type T = f64;
+    /// const N: usize = 1;
+    ///
+    /// let mut my_vec: SysVec<T, N> = SysVec::from_scalars(0.0, -1.0, 1.0);
+    /// my_vec.set_clamp_hysteresis(SVector::from_element(0.1));
+    /// assert_eq!(Some(vector![0.1]), my_vec.get_clamp_hysteresis());
+    ///
+    /// // The value reaches the upper bound and is clamped.
+    /// my_vec.update(vector![1.2]);
+    /// assert_eq!(vector![1.0], my_vec.get_val());
+    ///
+    /// // Hovering just inside the bound, within the band, it stays clamped.
+    /// for val in [0.99, 0.95, 1.05, 0.91] {
+    ///     my_vec.update(vector![val]);
+    ///     assert_eq!(vector![1.0], my_vec.get_val());
+    /// }
+    ///
+    /// // Past the band it is released.
+    /// my_vec.update(vector![0.85]);
+    /// assert_eq!(vector![0.85], my_vec.get_val());
+    ///
+    /// // Once released, a value inside the band is not clamped.
+    /// my_vec.update(vector![0.95]);
+    /// assert_eq!(vector![0.95], my_vec.get_val());
+    ///
+    /// // An integrator with a band on its state leaves the upper bound when the input reverses.
+    /// let mut x: SysVec<T, N> = SysVec::from_scalars(0.0, -1.0, 1.0);
+    /// x.set_clamp_hysteresis(SVector::from_element(0.1));
+    /// let mut sys: StateSpace<T, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0]).set_b(matrix![1.0]).set_c(matrix![1.0]).set_x(x).set_dt(0.1);
+    /// sys.set_u(SysVec::from_val(2.0)).update_n(10);
+    /// assert_eq!(vector![1.0], sys.get_x());
+    /// sys.set_u(SysVec::from_val(-0.5)).update_n(100);
+    /// assert_eq!(vector![-1.0], sys.get_x());
+    /// ```
+    pub fn set_clamp_hysteresis(&mut self, delta: SVector<T, N>) -> &mut Self {
+        self.hysteresis = Some(delta);
         self
     }
 
+    /// Getter for SysVec.hysteresis property, `None` if there is no hysteresis.
+    pub fn get_clamp_hysteresis(&self) -> Option<SVector<T, N>> {
+        self.hysteresis.clone()
+    }
+
     /// Getter for SysVec.val property. The output is an SVector.
     ///
     /// ```rust
@@ -199,6 +437,55 @@ where
         self.val.clone()
     }
 
+    /// Getter for a single element of SysVec.val, or `None` if `index >= N`.
+    ///
+    /// ```rust
+    /// use state_space::SysVec;
+    /// type T = f64;
+    /// const N: usize = 3;
+    ///
+    /// let my_vec: SysVec<T, N> = SysVec::from_scalars(1.017, -3.14, 6.28);
+    /// assert_eq!(Some(1.017), my_vec.get(2));
+    /// assert_eq!(None, my_vec.get(3));
+    /// ```
+    pub fn get(&self, index: usize) -> Option<T> {
+        self.val.get(index).cloned()
+    }
+
+    /// Reference accessor for SysVec.val property, which avoids the copy made by `get_val()`.
+    ///
+    /// ```rust
+    /// use nalgebra::SVector;
+    /// use state_space::SysVec;
+    /// type T = f64;
+    /// const N: usize = 3;
+    ///
+    /// let my_vec: SysVec<T, N> = SysVec::from_val(1.017);
+    ///
+    /// let exp_val: SVector<T, N> = SVector::from_element(1.017);
+    /// assert_eq!(&exp_val, my_vec.val_ref());
+    /// ```
+    pub fn val_ref(&self) -> &SVector<T, N> {
+        &self.val
+    }
+
+    /// Mutable reference accessor for SysVec.val property, to change single elements in place.
+    /// The value is not clamped until `clamp()` or `update()` is called.
+    ///
+    /// ```rust
+    /// use state_space::SysVec;
+    ///
+    /// let mut my_vec: SysVec<f64, 3> = SysVec::from_scalars(0.0, -1.0, 1.0);
+    /// my_vec.val_mut()[1] = 5.0;
+    /// assert_eq!(5.0, my_vec.get_val()[1]);
+    ///
+    /// my_vec.clamp();
+    /// assert_eq!(1.0, my_vec.get_val()[1]);
+    /// ```
+    pub fn val_mut(&mut self) -> &mut SVector<T, N> {
+        &mut self.val
+    }
+
     /// Getter for SysVec.lb property. The output is an SVector.
     ///
     /// ```rust
@@ -233,7 +520,64 @@ where
         self.ub.clone()
     }
 
-    /// Use the clamp() method to gurantee that all self.lb <= self.val <= self.ub.
+    /// Getter for SysVec.rate property. The output is an SVector, or `None` if there is no rate
+    /// limit. See `set_rate_limit()`.
+    pub fn get_rate_limit(&self) -> Option<SVector<T, N>> {
+        self.rate.clone()
+    }
+}
+
+// The methods which compare values, so they are not available for a complex T.
+impl<T, const N: usize> SysVec<T, N>
+where
+    T: nalgebra::Scalar + PartialOrd + num_traits::NumCast,
+{
+    /// Construct a SysVec struct from vectors for val, lb, and ub, so each channel can have its
+    /// own bounds, for example actuators with different travel. Returns
+    /// `StateSpaceError::InvalidBounds` if any element of `lb` is larger than the same element
+    /// of `ub`. The value is not clamped until `clamp()` or `update()` is called.
+    ///
+    /// ```rust
+    /// use nalgebra::vector;
+    /// use state_space::{StateSpaceError, SysVec};
+    /// type T = f64;
+    /// const N: usize = 3;
+    ///
+    /// let lb = vector![-1.0, -20.0, 0.0];
+    /// let ub = vector![1.0, 10.0, 0.5];
+    /// let mut my_vec: SysVec<T, N> =
+    ///     SysVec::from_vectors(vector![2.0, -5.0, 0.25], lb, ub).unwrap();
+    /// assert_eq!(lb, my_vec.get_lb());
+    /// assert_eq!(ub, my_vec.get_ub());
+    ///
+    /// my_vec.clamp();
+    /// assert_eq!(vector![1.0, -5.0, 0.25], my_vec.get_val());
+    ///
+    /// // The lower bound of the last element is above its upper bound.
+    /// let result = SysVec::from_vectors(vector![0.0, 0.0, 0.0], lb, vector![1.0, 10.0, -0.5]);
+    /// assert_eq!(Some(StateSpaceError::InvalidBounds), result.err());
+    /// ```
+    pub fn from_vectors(
+        val: SVector<T, N>,
+        lb: SVector<T, N>,
+        ub: SVector<T, N>,
+    ) -> Result<Self, StateSpaceError> {
+        if lb.iter().zip(ub.iter()).any(|(lb, ub)| lb > ub) {
+            return Err(StateSpaceError::InvalidBounds);
+        }
+        Ok(Self {
+            val,
+            lb,
+            ub,
+            rate: None,
+            hysteresis: None,
+            held: [Held::Free; N],
+        })
+    }
+
+    /// Use the clamp() method to gurantee that all self.lb <= self.val <= self.ub. With a
+    /// hysteresis band, see `set_clamp_hysteresis()`, an element held at a bound stays there
+    /// until its value is more than the band inside the bound.
     /// ///
     /// ```rust
     /// use nalgebra::{SVector, vector};
@@ -257,12 +601,56 @@ where
     /// assert_eq!(exp_val, my_vec.get_val())
     /// ```
     pub fn clamp(&mut self) -> &mut Self {
-        self.val = self
-            .val
-            .zip_zip_map(&self.lb, &self.ub, |x, min, max| na::clamp(x, min, max));
+        let band = match &self.hysteresis {
+            Some(band) => band,
+            None => return self.clamp_to_bounds(),
+        };
+        for i in 0..N {
+            let (lb, ub) = (self.lb[i].clone(), self.ub[i].clone());
+            let x = self.val[i].clone();
+            self.held[i] = match self.held[i] {
+                Held::Upper if !released(&x, &ub, &band[i], true) => Held::Upper,
+                Held::Lower if !released(&x, &lb, &band[i], false) => Held::Lower,
+                _ if x >= ub => Held::Upper,
+                _ if x <= lb => Held::Lower,
+                _ => Held::Free,
+            };
+            self.val[i] = match self.held[i] {
+                Held::Upper => ub,
+                Held::Lower => lb,
+                Held::Free => x,
+            };
+        }
         self
     }
 
+    /// Reports which elements of val are at (or beyond) their lower or upper bound. After
+    /// `clamp()`, or `StateSpace.update()`, this shows which channels are saturated.
+    ///
+    /// ```rust
+    /// use nalgebra::{vector, SVector};
+    /// use state_space::SysVec;
+    /// type T = f64;
+    /// const N: usize = 3;
+    ///
+    /// let mut my_vec: SysVec<T, N> = SysVec::from_scalars(0.0, -5.0, 5.0);
+    /// assert!(!my_vec.any_saturated());
+    ///
+    /// my_vec.set_val(vector![1.0, 7.0, -2.0]).clamp();
+    /// let exp: SVector<bool, N> = vector![false, true, false];
+    /// assert_eq!(exp, my_vec.is_saturated());
+    /// assert!(my_vec.any_saturated());
+    /// ```
+    pub fn is_saturated(&self) -> SVector<bool, N> {
+        self.val
+            .zip_zip_map(&self.lb, &self.ub, |x, min, max| x <= min || x >= max)
+    }
+
+    /// True if any element of val is at a bound, see `is_saturated()`.
+    pub fn any_saturated(&self) -> bool {
+        self.is_saturated().iter().any(|s| *s)
+    }
+
     /// This method is used in StateSpace.update(). It updates the val property and checks clamp.
     /// End users do not need to be concerned with this method.
     pub fn update(&mut self, val: SVector<T, N>) -> &mut Self {
@@ -270,6 +658,55 @@ where
         self.clamp();
         self
     }
+
+    // Used by StateSpace.update() for the state x. Clamps val to the bounds without the
+    // hysteresis band: x is stepped from its clamped value, so an element held at a bound
+    // could never move more than the band in one step and would stay at the bound.
+    pub(crate) fn clamp_to_bounds(&mut self) -> &mut Self {
+        self.val = self
+            .val
+            .zip_zip_map(&self.lb, &self.ub, |x, min, max| na::clamp(x, min, max));
+        self
+    }
+
+    // Used by StateSpace.update() for anti-windup. Returns val clamped to the bounds of self,
+    // self is not changed, so the hysteresis band is not applied.
+    pub(crate) fn clamped(&self, val: &SVector<T, N>) -> SVector<T, N> {
+        val.zip_zip_map(&self.lb, &self.ub, |x, min, max| na::clamp(x, min, max))
+    }
+
+    // Used by StateSpace.update() to limit the rate of change of the input. Returns val limited
+    // to within rate * dt of prev, val itself is not changed.
+    pub(crate) fn rate_limit(&self, prev: &SVector<T, N>, dt: T) -> SVector<T, N>
+    where
+        T: nalgebra::ClosedAddAssign + nalgebra::ClosedMulAssign,
+    {
+        let step = match &self.rate {
+            Some(rate) => rate * dt,
+            None => return self.val.clone(),
+        };
+        let lower = prev + &step * crate::neg_one::<T>();
+        let upper = prev + &step;
+        self.val
+            .zip_zip_map(&lower, &upper, |x, min, max| na::clamp(x, min, max))
+    }
+}
+
+// True if x is more than band inside bound, which is the upper bound if upper, so an element
+// held at the bound is released. The distance is found as f64, so clamp() needs no arithmetic
+// on T. An element is released if a value can not be converted.
+fn released<T>(x: &T, bound: &T, band: &T, upper: bool) -> bool
+where
+    T: nalgebra::Scalar + num_traits::NumCast,
+{
+    let x: Option<f64> = num_traits::cast(x.clone());
+    let bound: Option<f64> = num_traits::cast(bound.clone());
+    let band: Option<f64> = num_traits::cast(band.clone());
+    match (x, bound, band) {
+        (Some(x), Some(bound), Some(band)) if upper => bound - x > band,
+        (Some(x), Some(bound), Some(band)) => x - bound > band,
+        _ => true,
+    }
 }
 
 impl<T, const N: usize> Default for SysVec<T, N>
@@ -0,0 +1,352 @@
+/*!
+# transfer_function
+The `transfer_function` module converts between a SISO `StateSpace` and the numerator and
+denominator polynomials of its transfer function, as used by MATLAB's `tf()`.
+
+## Features.
+* `from_tf()` builds the controllable canonical form realization of a transfer function, the same
+  realization as MATLAB's `tf2ss()`.
+* `from_zpk()` realizes a SISO transfer function from its zeros, poles, and gain, as MATLAB's
+  `zpk()`.
+* `from_tf_matrix()` realizes a MIMO system from a matrix of SISO transfer functions.
+* `to_tf()` returns the numerator and denominator of a SISO system, the same as MATLAB's
+  `ss2tf()`. It returns `Vec`s, so it requires the `std` feature.
+*/
+
+#[cfg(feature = "std")]
+use na::SMatrix;
+use na::{Complex, RealField, SVector};
+use nalgebra as na;
+use num_traits::NumCast;
+
+use crate::transform::real_poly_from_roots;
+use crate::{StateSpace, StateSpaceError};
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: RealField + NumCast,
+{
+    /// Realization of a matrix of SISO transfer functions, `G[i][j] = nums[i][j] / dens[i][j]`
+    /// from input `j` to output `i`. Each entry is realized in controllable canonical form, see
+    /// `from_tf()`, and the realizations are placed on the diagonal of `A`, in row major order of
+    /// the entries. So `NX` is the sum of the degrees of all the denominators. The realization
+    /// is not minimal when entries share poles, `balance_and_truncate()` can remove the extra
+    /// states.
+    ///
+    /// An entry with no coupling is `0 / 1`, which uses no states. The coefficients can be given
+    /// as `Vec`s or slices.
+    ///
+    /// Returns an error if an entry is invalid, see `from_tf()`, or if `NX` is not the sum of
+    /// the degrees of the denominators, `StateSpaceError::DimensionMismatch`.
+    ///
+    /// This example realizes `diag(2 / (s + 1), 3 / (s + 2))`. Each input only moves its own
+    /// output, with the same step response as the SISO realization.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let nums = [[vec![2.0], vec![0.0]], [vec![0.0], vec![3.0]]];
+    /// let dens = [[vec![1.0, 1.0], vec![1.0]], [vec![1.0], vec![1.0, 2.0]]];
+    /// let mut sys: StateSpace<f64, 2, 2, 2> = StateSpace::from_tf_matrix(&nums, &dens).unwrap();
+    /// sys.set_dt(0.01).set_u(SysVec::from_val(0.0));
+    /// sys.get_u_mut().val_mut()[0] = 1.0;
+    ///
+    /// let mut siso: StateSpace<f64, 1, 1, 1> =
+    ///     StateSpace::from_tf(&[2.0], &[1.0, 1.0]).unwrap();
+    /// siso.set_dt(0.01).set_u(SysVec::from_val(1.0));
+    ///
+    /// for _ in 0..200 {
+    ///     sys.update();
+    ///     siso.update();
+    ///     assert_eq!(siso.get_y()[0], sys.get_y()[0]);
+    ///     assert_eq!(0.0, sys.get_y()[1]);
+    /// }
+    ///
+    /// // The same for the second input and output.
+    /// sys.reset_all().set_u(SysVec::from_val(0.0));
+    /// sys.get_u_mut().val_mut()[1] = 1.0;
+    /// let mut siso: StateSpace<f64, 1, 1, 1> =
+    ///     StateSpace::from_tf(&[3.0], &[1.0, 2.0]).unwrap();
+    /// siso.set_dt(0.01).set_u(SysVec::from_val(1.0));
+    /// for _ in 0..200 {
+    ///     sys.update();
+    ///     siso.update();
+    ///     assert_eq!(0.0, sys.get_y()[0]);
+    ///     assert_eq!(siso.get_y()[0], sys.get_y()[1]);
+    /// }
+    ///
+    /// // One more state than the denominators need.
+    /// let err = StateSpace::<f64, 2, 3, 2>::from_tf_matrix(&nums, &dens).err();
+    /// assert_eq!(Some(state_space::StateSpaceError::DimensionMismatch), err);
+    /// ```
+    pub fn from_tf_matrix<P: AsRef<[T]>>(
+        nums: &[[P; NU]; NY],
+        dens: &[[P; NU]; NY],
+    ) -> Result<Self, StateSpaceError> {
+        let mut sys = Self::new();
+        let mut offset = 0;
+        for i in 0..NY {
+            for j in 0..NU {
+                offset +=
+                    sys.realize_entry(nums[i][j].as_ref(), dens[i][j].as_ref(), i, j, offset)?;
+            }
+        }
+        if offset != NX {
+            return Err(StateSpaceError::DimensionMismatch);
+        }
+        Ok(sys)
+    }
+
+    // Writes the controllable canonical form of num / den from input j to output i, with its
+    // states starting at offset. Returns the number of states, the degree of den.
+    fn realize_entry(
+        &mut self,
+        num: &[T],
+        den: &[T],
+        i: usize,
+        j: usize,
+        offset: usize,
+    ) -> Result<usize, StateSpaceError> {
+        if den.is_empty() || den[0] == T::zero() {
+            return Err(StateSpaceError::InvalidPolynomial);
+        }
+
+        // Leading zeros of the numerator do not change its degree.
+        let first = num
+            .iter()
+            .position(|b| *b != T::zero())
+            .unwrap_or(num.len());
+        let num = &num[first..];
+        if num.len() > den.len() {
+            return Err(StateSpaceError::NotProper);
+        }
+        let n = den.len() - 1;
+        if offset + n > NX {
+            return Err(StateSpaceError::DimensionMismatch);
+        }
+
+        // Normalize so that den[0] = 1, and pad the numerator to the length of the denominator.
+        let lead = den[0].clone();
+        let pad = den.len() - num.len();
+        let b_at = |k: usize| {
+            if k < pad {
+                T::zero()
+            } else {
+                num[k - pad].clone() / lead.clone()
+            }
+        };
+        let d = b_at(0);
+
+        for k in 0..n {
+            let a_k = den[k + 1].clone() / lead.clone();
+            self.a[(offset, offset + k)] = -a_k.clone();
+            self.c[(i, offset + k)] = b_at(k + 1) - d.clone() * a_k;
+            if k > 0 {
+                self.a[(offset + k, offset + k - 1)] = T::one();
+            }
+        }
+        if n > 0 {
+            self.b[(offset, j)] = T::one();
+        }
+        self.d[(i, j)] = d;
+        Ok(n)
+    }
+}
+
+impl<T, const NX: usize> StateSpace<T, 1, NX, 1>
+where
+    T: RealField + NumCast,
+{
+    /// Controllable canonical form realization of the transfer function `num(s) / den(s)`. The
+    /// coefficients are in descending powers of `s`, the same as MATLAB's `tf(num, den)`.
+    ///
+    /// The denominator is normalized by its leading coefficient `den[0]`, so for
+    /// `den = [1, a1, ..., an]` the matrices are:
+    /// * `A` has `-a1, ..., -an` in its first row and ones on the subdiagonal.
+    /// * `B` is the first unit vector.
+    /// * `C` is the numerator with the feedthrough removed, `b_i - D a_i`.
+    /// * `D` is the feedthrough, which is only non-zero when the degrees of `num` and `den` are
+    ///   equal.
+    ///
+    /// The returned system is continuous with the default `dt`, which should be set with
+    /// `set_dt()` before it is stepped.
+    ///
+    /// Returns an error if:
+    /// * `den` is empty or `den[0]` is zero, `StateSpaceError::InvalidPolynomial`.
+    /// * The degree of `num` is larger than the degree of `den`, `StateSpaceError::NotProper`.
+    /// * The degree of `den` is not `NX`, `StateSpaceError::DimensionMismatch`.
+    ///
+    /// This example realizes `tf(w^2, [1, 2*z*w, w^2])` and checks that it has the same step
+    /// response as the hand written matrices of Example 2.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, StateSpaceError};
+    ///
+    /// let w = 2.0 * std::f64::consts::PI;
+    /// let z = 0.707_f64;
+    /// let mut sys: StateSpace<f64, 1, 2, 1> =
+    ///     StateSpace::from_tf(&[w * w], &[1.0, 2.0 * z * w, w * w]).unwrap();
+    /// sys.set_dt(0.01);
+    ///
+    /// let mut example: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// example
+    ///     .set_a(matrix![0.0, 1.0; -w * w, -2.0 * z * w])
+    ///     .set_b(matrix![0.0; w * w])
+    ///     .set_c(matrix![1.0, 0.0])
+    ///     .set_dt(0.01);
+    ///
+    /// let y = sys.step_response(300, None);
+    /// let y_example = example.step_response(300, None);
+    /// for (a, b) in y.iter().zip(y_example.iter()) {
+    ///     assert!((a[0] - b[0]).abs() < 1e-9);
+    /// }
+    ///
+    /// // A second order denominator can not be realized with one state.
+    /// let err = StateSpace::<f64, 1, 1, 1>::from_tf(&[1.0], &[1.0, 2.0, 1.0]).err();
+    /// assert_eq!(Some(StateSpaceError::DimensionMismatch), err);
+    ///
+    /// // An improper transfer function has no state space realization.
+    /// let err = StateSpace::<f64, 1, 1, 1>::from_tf(&[1.0, 0.0, 0.0], &[1.0, 1.0]).err();
+    /// assert_eq!(Some(StateSpaceError::NotProper), err);
+    /// ```
+    pub fn from_tf(num: &[T], den: &[T]) -> Result<Self, StateSpaceError> {
+        let mut sys = Self::new();
+        if sys.realize_entry(num, den, 0, 0, 0)? != NX {
+            return Err(StateSpaceError::DimensionMismatch);
+        }
+        Ok(sys)
+    }
+
+    /// Realization of the transfer function with the given zeros, poles, and gain, the same as
+    /// MATLAB's `zpk(zeros, poles, gain)`:
+    /// ```text
+    /// G(s) = gain * (s - z1) ... (s - zm) / ((s - p1) ... (s - pn))
+    /// ```
+    /// The polynomials are expanded and realized in controllable canonical form, the same
+    /// realization as `from_tf()`. Complex zeros and poles must be given with their conjugates,
+    /// so that the realization is real.
+    ///
+    /// Returns an error if:
+    /// * There are not `NX` poles, `StateSpaceError::DimensionMismatch`.
+    /// * There are more zeros than poles, `StateSpaceError::NotProper`.
+    /// * A complex zero or pole has no conjugate, `StateSpaceError::InvalidPolynomial`.
+    ///
+    /// ```rust
+    /// use nalgebra::Complex;
+    /// use state_space::{StateSpace, StateSpaceError};
+    ///
+    /// // A first order lag, 1 / (s + 1).
+    /// let lag: StateSpace<f64, 1, 1, 1> =
+    ///     StateSpace::from_zpk(&[], &[Complex::new(-1.0, 0.0)], 1.0).unwrap();
+    /// let tf: StateSpace<f64, 1, 1, 1> = StateSpace::from_tf(&[1.0], &[1.0, 1.0]).unwrap();
+    /// assert!(lag.approx_eq(&tf, 1e-12));
+    ///
+    /// // 2 (s + 3) / ((s + 1 - 2j)(s + 1 + 2j)) = (2 s + 6) / (s^2 + 2 s + 5).
+    /// let poles = [Complex::new(-1.0, 2.0), Complex::new(-1.0, -2.0)];
+    /// let sys: StateSpace<f64, 1, 2, 1> =
+    ///     StateSpace::from_zpk(&[Complex::new(-3.0, 0.0)], &poles, 2.0).unwrap();
+    /// let tf: StateSpace<f64, 1, 2, 1> =
+    ///     StateSpace::from_tf(&[2.0, 6.0], &[1.0, 2.0, 5.0]).unwrap();
+    /// assert!(sys.approx_eq(&tf, 1e-12));
+    ///
+    /// let err = StateSpace::<f64, 1, 2, 1>::from_zpk(&[], &poles[..1], 1.0).err();
+    /// assert_eq!(Some(StateSpaceError::DimensionMismatch), err);
+    /// let err = StateSpace::<f64, 1, 2, 1>::from_zpk(&[], &[poles[0], poles[0]], 1.0).err();
+    /// assert_eq!(Some(StateSpaceError::InvalidPolynomial), err);
+    /// ```
+    pub fn from_zpk(
+        zeros: &[Complex<T>],
+        poles: &[Complex<T>],
+        gain: T,
+    ) -> Result<Self, StateSpaceError> {
+        if poles.len() != NX {
+            return Err(StateSpaceError::DimensionMismatch);
+        }
+        if zeros.len() > NX {
+            return Err(StateSpaceError::NotProper);
+        }
+        let a: SVector<T, NX> = real_poly_from_roots(poles)?;
+        let b: SVector<T, NX> = real_poly_from_roots(zeros)?;
+
+        // The numerator gain * [1, b1, ..., bm], padded to the NX + 1 coefficients of the
+        // denominator.
+        let pad = NX - zeros.len();
+        let b_at = |k: usize| match k {
+            k if k < pad => T::zero(),
+            k if k == pad => gain.clone(),
+            k => gain.clone() * b[k - pad - 1].clone(),
+        };
+        let d = b_at(0);
+
+        let mut sys = Self::new();
+        for k in 0..NX {
+            sys.a[(0, k)] = -a[k].clone();
+            sys.c[(0, k)] = b_at(k + 1) - d.clone() * a[k].clone();
+            if k > 0 {
+                sys.a[(k, k - 1)] = T::one();
+            }
+        }
+        if NX > 0 {
+            sys.b[(0, 0)] = T::one();
+        }
+        sys.d[(0, 0)] = d;
+        Ok(sys)
+    }
+
+    /// Numerator and denominator of the transfer function `C (sI - A)^{-1} B + D`, as
+    /// `(num, den)` in descending powers of `s`. Both have `NX + 1` coefficients and `den` is
+    /// monic, `den[0] = 1`. The coefficients are the same as MATLAB's `ss2tf()`, and can be
+    /// passed back to `from_tf()`.
+    ///
+    /// The denominator is the characteristic polynomial of A and `adj(sI - A)` is found at the
+    /// same time with the Faddeev-LeVerrier recursion, so no polynomial roots are needed. A
+    /// discrete system returns the coefficients of `z` rather than `s`.
+    ///
+    /// This example converts the first order system of Example 1 to `1 / (s + 1)`, and realizes
+    /// it again with `from_tf()` to compare the step responses.
+    ///
+    /// ```rust
+    /// use nalgebra::SMatrix;
+    /// use state_space::StateSpace;
+    ///
+    /// let mut sys: StateSpace<f64, 1, 1, 1> = StateSpace::new();
+    /// sys.set_a(-1.0 * SMatrix::identity())
+    ///     .set_b(SMatrix::identity())
+    ///     .set_c(SMatrix::identity())
+    ///     .set_dt(0.1);
+    ///
+    /// let (num, den) = sys.to_tf();
+    /// assert_eq!(vec![0.0, 1.0], num);
+    /// assert_eq!(vec![1.0, 1.0], den);
+    ///
+    /// let mut round_trip: StateSpace<f64, 1, 1, 1> = StateSpace::from_tf(&num, &den).unwrap();
+    /// round_trip.set_dt(0.1);
+    /// let y = sys.step_response(50, None);
+    /// let y_round_trip = round_trip.step_response(50, None);
+    /// assert_eq!(y, y_round_trip);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn to_tf(&self) -> (Vec<T>, Vec<T>) {
+        let eye: SMatrix<T, NX, NX> = SMatrix::identity();
+        let d = self.d[(0, 0)].clone();
+
+        let mut den = vec![T::one()];
+        let mut num = vec![d.clone()];
+
+        // Faddeev-LeVerrier, adj(sI - A) = sum(M_k s^(NX-k)) with M_1 = I and
+        // M_(k+1) = A M_k + den[k] I, den[k] = -trace(A M_k) / k.
+        let mut m_k = eye.clone();
+        for k in 1..=NX {
+            let a_m = self.a.clone() * m_k.clone();
+            let kt = T::from(k).expect("Conversion failed");
+            let den_k = -a_m.trace() / kt;
+            let cmb = (self.c.clone() * m_k.clone() * self.b.clone())[(0, 0)].clone();
+            num.push(cmb + d.clone() * den_k.clone());
+            m_k = a_m + eye.clone() * den_k.clone();
+            den.push(den_k);
+        }
+        (num, den)
+    }
+}
@@ -0,0 +1,307 @@
+/*!
+# transform
+The `transform` module changes the realization of a `StateSpace` system, the choice of states,
+without changing its transfer function, or the unit of its time axis.
+
+## Features.
+* `transform()` applies the similarity transform `x = T z`.
+* `to_controllable_form()` converts a SISO system to controllable canonical form, for pole
+  placement.
+* `scale_time()` rescales the time axis, for example from seconds to milliseconds.
+*/
+
+use na::{Complex, RealField, SMatrix, SVector};
+use nalgebra as na;
+use num_traits::NumCast;
+
+use crate::{StateSpace, StateSpaceError, SysVec, SystemTime};
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: RealField + NumCast,
+{
+    /// Applies the similarity transform `x = T z`, the system with the state `z`:
+    /// ```text
+    /// Az = T^-1 A T    Bz = T^-1 B    Cz = C T    Dz = D
+    /// ```
+    /// The transfer function and eigenvalues are unchanged. The state is transformed too,
+    /// `z = T^-1 x`, with default bounds since the bounds of `x` do not carry over to `z`. The
+    /// names of the states are dropped for the same reason. The state feedback gain becomes
+    /// `Kz = K T`, so the closed loop is unchanged. The other settings, such as `dt`, the
+    /// integrator, and `u` and `y` with their bounds and names, are kept.
+    ///
+    /// Returns `StateSpaceError::SingularMatrix` if `T` is singular.
+    ///
+    /// ```rust
+    /// use nalgebra::{matrix, SMatrix};
+    /// use state_space::{Rk4, StateSpace, StateSpaceError, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; -2.0, -3.0])
+    ///     .set_b(matrix![0.0; 1.0])
+    ///     .set_c(matrix![1.0, 0.0])
+    ///     .set_d(matrix![0.5]);
+    ///
+    /// // The identity leaves the system unchanged.
+    /// let same = sys.transform(SMatrix::identity()).unwrap();
+    /// assert_eq!(sys.get_a(), same.get_a());
+    /// assert_eq!(sys.get_b(), same.get_b());
+    /// assert_eq!(sys.get_c(), same.get_c());
+    /// assert_eq!(sys.get_d(), same.get_d());
+    ///
+    /// // A diagonal T scales the states, z = [x0 / 2, x1 / 4].
+    /// let scaled = sys.transform(matrix![2.0, 0.0; 0.0, 4.0]).unwrap();
+    /// assert_eq!(matrix![0.0, 2.0; -1.0, -3.0], scaled.get_a());
+    /// assert_eq!(matrix![0.0; 0.25], scaled.get_b());
+    /// assert_eq!(matrix![2.0, 0.0], scaled.get_c());
+    /// assert_eq!(matrix![0.5], scaled.get_d());
+    ///
+    /// let err = sys.transform(matrix![1.0, 2.0; 2.0, 4.0]).err();
+    /// assert_eq!(Some(StateSpaceError::SingularMatrix), err);
+    ///
+    /// // A closed loop with RK4 gives the same output after the transform.
+    /// sys.set_feedback_gain(matrix![1.0, 2.0])
+    ///     .set_x(SysVec::from_val(1.0))
+    ///     .set_dt(0.1)
+    ///     .set_integrator::<Rk4>();
+    /// let mut scaled = sys.transform(matrix![2.0, 0.0; 0.0, 4.0]).unwrap();
+    /// for _ in 0..50 {
+    ///     sys.update();
+    ///     scaled.update();
+    ///     assert!((sys.get_y()[0] - scaled.get_y()[0]).abs() < 1e-12);
+    /// }
+    /// ```
+    pub fn transform(
+        &self,
+        t: SMatrix<T, NX, NX>,
+    ) -> Result<StateSpace<T, NU, NX, NY>, StateSpaceError> {
+        let t_inv = t
+            .clone()
+            .try_inverse()
+            .ok_or(StateSpaceError::SingularMatrix)?;
+
+        let mut z: SysVec<T, NX> = SysVec::new();
+        z.set_val(&t_inv * self.x.val_ref());
+
+        let mut sys = self.clone();
+        sys.a = &t_inv * &self.a * &t;
+        sys.b = &t_inv * &self.b;
+        sys.c = &self.c * &t;
+        sys.feedback_gain = &self.feedback_gain * t;
+        sys.x = z;
+        sys.state_names = None;
+        Ok(sys)
+    }
+
+    /// Rescales the time axis of a continuous system, so the dynamics run `factor` times faster
+    /// in the new unit of time: `A' = A * factor`, `B' = B * factor`, and `dt' = dt / factor`.
+    /// C, D, and the state are unchanged. Since `A' dt' = A dt`, each step of `update()` covers
+    /// the same part of the response, only the unit changes. For example a model in seconds is
+    /// converted to milliseconds with a factor of `0.001`, and its `dt` of `0.01` s becomes
+    /// `10` ms. Call `set_dt()` afterwards to change the step in the new unit.
+    ///
+    /// Returns an error, and leaves the system unchanged, if:
+    /// * The system is discrete, `StateSpaceError::SystemTimeMismatch`. A discrete A is the map
+    ///   of one step, which has no time axis to scale.
+    /// * `factor` is zero or negative, `StateSpaceError::NonPositiveDt`.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, SysVec};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![0.0, 1.0; -4.0, -2.0])
+    ///     .set_b(matrix![0.0; 4.0])
+    ///     .set_c(matrix![1.0, 0.0])
+    ///     .set_u(SysVec::from_val(1.0))
+    ///     .set_dt(0.01);
+    ///
+    /// // Twice as fast, with half the dt, the steps are the same.
+    /// let mut fast = sys;
+    /// fast.scale_time(2.0).unwrap();
+    /// assert_eq!(sys.get_a() * 2.0, fast.get_a());
+    /// assert_eq!(0.005, fast.get_dt());
+    /// let (mut slow_steps, mut fast_steps) = (sys, fast);
+    /// for _ in 0..100 {
+    ///     slow_steps.update();
+    ///     fast_steps.update();
+    ///     assert_eq!(slow_steps.get_x(), fast_steps.get_x());
+    /// }
+    ///
+    /// // With the same dt, half as many exact (ZOH) steps reach every second point.
+    /// fast.set_dt(0.01);
+    /// let (mut slow, mut fast) = (sys.to_discrete(), fast.to_discrete());
+    /// for _ in 0..100 {
+    ///     slow.update().update();
+    ///     fast.update();
+    ///     assert!((slow.get_x() - fast.get_x()).norm() < 1e-12);
+    /// }
+    ///
+    /// assert!(sys.scale_time(0.0).is_err());
+    /// assert!(slow.scale_time(2.0).is_err());
+    /// ```
+    pub fn scale_time(&mut self, factor: T) -> Result<&mut Self, StateSpaceError> {
+        if self.time == SystemTime::Discrete {
+            return Err(StateSpaceError::SystemTimeMismatch);
+        }
+        if factor <= T::zero() {
+            return Err(StateSpaceError::NonPositiveDt);
+        }
+        self.a *= factor.clone();
+        self.b *= factor.clone();
+        self.dt = self.dt.clone() / factor;
+        Ok(self)
+    }
+}
+
+// Coefficients [a1, ..., an] of the characteristic polynomial det(sI - A) = s^n + a1 s^(n-1) +
+// ... + an, with the Faddeev-LeVerrier recursion M_(k+1) = A M_k + a_k I, a_k = -trace(A M_k) / k.
+pub(crate) fn char_poly<T: RealField + NumCast, const N: usize>(
+    a: &SMatrix<T, N, N>,
+) -> SVector<T, N> {
+    let eye: SMatrix<T, N, N> = SMatrix::identity();
+    let mut coeffs: SVector<T, N> = SVector::zeros();
+    let mut m_k = eye.clone();
+    for k in 1..=N {
+        let a_m = a * m_k;
+        let a_k = -a_m.trace() / T::from(k).expect("Conversion failed");
+        m_k = a_m + &eye * a_k.clone();
+        coeffs[k - 1] = a_k;
+    }
+    coeffs
+}
+
+// Coefficients [c1, ..., cm] of the monic polynomial (s - r1) ... (s - rm) = s^m + c1 s^(m-1) +
+// ... + cm, in the first m elements, multiplying in one root at a time. The imaginary parts
+// cancel when the complex roots come in conjugate pairs, InvalidPolynomial if they do not. The
+// caller checks that m <= N.
+pub(crate) fn real_poly_from_roots<T: RealField, const N: usize>(
+    roots: &[Complex<T>],
+) -> Result<SVector<T, N>, StateSpaceError> {
+    let mut coeffs: SVector<Complex<T>, N> =
+        SVector::from_element(Complex::new(T::zero(), T::zero()));
+    for (m, r) in roots.iter().enumerate() {
+        for k in (0..=m).rev() {
+            let prev = if k == 0 {
+                Complex::new(T::one(), T::zero())
+            } else {
+                coeffs[k - 1].clone()
+            };
+            coeffs[k] = coeffs[k].clone() - r.clone() * prev;
+        }
+    }
+
+    let tol = T::default_epsilon().sqrt();
+    let mut coeffs_re: SVector<T, N> = SVector::zeros();
+    for (re, c) in coeffs_re.iter_mut().zip(coeffs.iter()) {
+        if c.im.clone().abs() > tol.clone() * (T::one() + c.re.clone().abs()) {
+            return Err(StateSpaceError::InvalidPolynomial);
+        }
+        *re = c.re.clone();
+    }
+    Ok(coeffs_re)
+}
+
+// The controllability matrix [b, Ab, ..., A^(n-1) b] of a single input.
+pub(crate) fn siso_ctrb<T: RealField, const N: usize>(
+    a: &SMatrix<T, N, N>,
+    b: &SMatrix<T, N, 1>,
+) -> SMatrix<T, N, N> {
+    let mut w: SMatrix<T, N, N> = SMatrix::zeros();
+    let mut col = b.clone();
+    for k in 0..N {
+        w.set_column(k, &col);
+        col = a * col;
+    }
+    w
+}
+
+impl<T, const NX: usize, const NY: usize> StateSpace<T, 1, NX, NY>
+where
+    T: RealField + NumCast,
+{
+    /// Transforms a single input system to controllable canonical form, with ones on the
+    /// superdiagonal of `A`, the negated coefficients of the characteristic polynomial
+    /// `s^n + a1 s^(n-1) + ... + an` in the last row, and `B` the last unit vector:
+    /// ```text
+    ///     [  0    1   ...  0  ]        [ 0 ]
+    /// A = [  :    :        :  ]    B = [ : ]
+    ///     [  0    0   ...  1  ]        [ 0 ]
+    ///     [ -an -an-1 ... -a1 ]        [ 1 ]
+    /// ```
+    /// In this form the state feedback `u = -K z` adds `K` to the last row of `A`, which makes
+    /// pole placement simple. The transform is `T = W_x W_z^-1`, from the controllability
+    /// matrices of the system and of the canonical form, see `transform()`.
+    ///
+    /// Returns `StateSpaceError::NotControllable` if the system is not controllable, with the
+    /// rank test of `is_controllable()`. Without the `std` feature only an exactly singular
+    /// controllability matrix is detected.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, StateSpaceError};
+    ///
+    /// // Poles at -1 and -3, so the characteristic polynomial is s^2 + 4 s + 3.
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.set_a(matrix![-1.0, 2.0; 0.0, -3.0])
+    ///     .set_b(matrix![1.0; 1.0])
+    ///     .set_c(matrix![1.0, 0.0]);
+    ///
+    /// let canonical = sys.to_controllable_form().unwrap();
+    /// assert!((canonical.get_a() - matrix![0.0, 1.0; -3.0, -4.0]).norm() < 1e-12);
+    /// assert!((canonical.get_b() - matrix![0.0; 1.0]).norm() < 1e-12);
+    ///
+    /// // The transfer function is unchanged.
+    /// let (num, _) = sys.to_tf();
+    /// let (num_c, _) = canonical.to_tf();
+    /// assert!(num.iter().zip(num_c.iter()).all(|(a, b)| (a - b).abs() < 1e-12));
+    ///
+    /// // The second state can not be reached from the input.
+    /// sys.set_a(matrix![-1.0, 0.0; 0.0, -3.0]).set_b(matrix![1.0; 0.0]);
+    /// let err = sys.to_controllable_form().err();
+    /// assert_eq!(Some(StateSpaceError::NotControllable), err);
+    ///
+    /// // Two modes which are equal to rounding error can not both be controlled.
+    /// sys.set_a(matrix![-1.0, 0.0; 0.0, -1.0 - 1e-15]).set_b(matrix![1.0; 1.0]);
+    /// assert!(!sys.is_controllable());
+    /// let err = sys.to_controllable_form().err();
+    /// assert_eq!(Some(StateSpaceError::NotControllable), err);
+    /// ```
+    pub fn to_controllable_form(&self) -> Result<StateSpace<T, 1, NX, NY>, StateSpaceError> {
+        let w_x = self.checked_ctrb()?;
+
+        // The canonical form with the same characteristic polynomial.
+        let coeffs = char_poly(&self.a);
+        let mut a_z: SMatrix<T, NX, NX> = SMatrix::zeros();
+        let mut b_z: SMatrix<T, NX, 1> = SMatrix::zeros();
+        for i in 0..NX {
+            if i + 1 < NX {
+                a_z[(i, i + 1)] = T::one();
+            }
+            a_z[(NX - 1, i)] = -coeffs[NX - 1 - i].clone();
+        }
+        if NX > 0 {
+            b_z[NX - 1] = T::one();
+        }
+        let w_z_inv = siso_ctrb(&a_z, &b_z)
+            .try_inverse()
+            .ok_or(StateSpaceError::SingularMatrix)?;
+
+        self.transform(w_x * w_z_inv)
+    }
+
+    // The controllability matrix, see siso_ctrb(), or StateSpaceError::NotControllable if the
+    // system is not controllable. The rank test of is_controllable() needs std, without it only
+    // a singular matrix is detected.
+    pub(crate) fn checked_ctrb(&self) -> Result<SMatrix<T, NX, NX>, StateSpaceError> {
+        let w = siso_ctrb(&self.a, &self.b);
+        #[cfg(feature = "std")]
+        let controllable = self.is_controllable();
+        #[cfg(not(feature = "std"))]
+        let controllable = w.clone().try_inverse().is_some();
+        if !controllable {
+            return Err(StateSpaceError::NotControllable);
+        }
+        Ok(w)
+    }
+}
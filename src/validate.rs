@@ -0,0 +1,127 @@
+/*!
+# validate
+The `validate` module checks the matrices of a `StateSpace` system before they are used, so a
+bad export from MATLAB or a file is caught when it is loaded rather than part way through a run.
+
+## Features.
+* `try_set_a()`, `try_set_b()`, `try_set_c()`, and `try_set_d()` reject a matrix with a NaN or
+  infinite element, which would otherwise poison every later state and output.
+* `is_symmetric()` and `is_positive_definite()` check a weight or covariance matrix, such as the
+  `Q` and `R` of `lqr()` and `KalmanFilter::try_new()`, which call them to validate their inputs.
+
+The finite checks need the notion of a finite value, so they are only available for a floating
+point `T`, one which implements `num_traits::float::FloatCore` such as `f32` and `f64`.
+`FloatCore` does not need `std`, so the checks are also available without the `std` feature, as
+are `is_symmetric()` and `is_positive_definite()`.
+*/
+
+use na::{RealField, SMatrix};
+use nalgebra as na;
+use num_traits::float::FloatCore;
+use num_traits::{NumCast, One, Zero};
+
+use crate::{StateSpace, StateSpaceError};
+
+/// True if `m` is symmetric, every element differs from its mirror across the diagonal by at most
+/// `tol`.
+///
+/// ```rust
+/// use nalgebra::matrix;
+/// use state_space::is_symmetric;
+///
+/// assert!(is_symmetric(&matrix![2.0, 1.0; 1.0, 3.0], 0.0));
+/// assert!(!is_symmetric(&matrix![2.0, 1.0; 0.0, 3.0], 1e-9));
+///
+/// // A matrix which is symmetric up to round off.
+/// assert!(is_symmetric(&matrix![2.0, 0.1 + 0.2; 0.3, 3.0], 1e-12));
+/// ```
+pub fn is_symmetric<T: RealField, const N: usize>(m: &SMatrix<T, N, N>, tol: T) -> bool {
+    (0..N).all(|i| (0..i).all(|j| (m[(i, j)].clone() - m[(j, i)].clone()).abs() <= tol))
+}
+
+/// True if `m` is positive definite, `x^T m x > 0` for every non-zero `x`, which is checked with a
+/// Cholesky decomposition. The decomposition only reads the lower triangle, so check a matrix
+/// which may not be symmetric with `is_symmetric()` first.
+///
+/// ```rust
+/// use nalgebra::matrix;
+/// use state_space::is_positive_definite;
+///
+/// assert!(is_positive_definite(&matrix![2.0, 1.0; 1.0, 3.0]));
+///
+/// // Symmetric, with the eigenvalues 3 and -1.
+/// assert!(!is_positive_definite(&matrix![1.0, 2.0; 2.0, 1.0]));
+///
+/// // Positive semi-definite is not enough.
+/// assert!(!is_positive_definite(&matrix![1.0, 0.0; 0.0, 0.0]));
+/// ```
+pub fn is_positive_definite<T: RealField, const N: usize>(m: &SMatrix<T, N, N>) -> bool {
+    m.clone().cholesky().is_some()
+}
+
+// The tolerance of the symmetry checks of the design methods, relative to the size of m.
+pub(crate) fn symmetry_tol<T: RealField, const N: usize>(m: &SMatrix<T, N, N>) -> T {
+    m.norm() * T::default_epsilon().sqrt()
+}
+
+// Returns StateSpaceError::NonFinite if an element of m is NaN or infinite.
+fn check_finite<T: FloatCore, const R: usize, const C: usize>(
+    m: &SMatrix<T, R, C>,
+) -> Result<(), StateSpaceError> {
+    if m.iter().all(|v| v.is_finite()) {
+        Ok(())
+    } else {
+        Err(StateSpaceError::NonFinite)
+    }
+}
+
+impl<T, const NU: usize, const NX: usize, const NY: usize> StateSpace<T, NU, NX, NY>
+where
+    T: nalgebra::Scalar
+        + nalgebra::ClosedAddAssign
+        + nalgebra::ClosedMulAssign
+        + PartialOrd
+        + One
+        + Zero
+        + NumCast
+        + FloatCore,
+{
+    /// Fallible version of `set_a()`. Returns `StateSpaceError::NonFinite`, and leaves A
+    /// unchanged, if an element is NaN or infinite.
+    ///
+    /// ```rust
+    /// use nalgebra::matrix;
+    /// use state_space::{StateSpace, StateSpaceError};
+    ///
+    /// let mut sys: StateSpace<f64, 1, 2, 1> = StateSpace::new();
+    /// sys.try_set_a(matrix![0.0, 1.0; -2.0, -3.0]).unwrap();
+    ///
+    /// let err = sys.try_set_a(matrix![0.0, 1.0; f64::INFINITY, -3.0]).err();
+    /// assert_eq!(Some(StateSpaceError::NonFinite), err);
+    /// let err = sys.try_set_b(matrix![f64::NAN; 1.0]).err();
+    /// assert_eq!(Some(StateSpaceError::NonFinite), err);
+    /// assert_eq!(matrix![0.0, 1.0; -2.0, -3.0], sys.get_a());
+    /// ```
+    pub fn try_set_a(&mut self, mat: SMatrix<T, NX, NX>) -> Result<&mut Self, StateSpaceError> {
+        check_finite(&mat)?;
+        Ok(self.set_a(mat))
+    }
+
+    /// Fallible version of `set_b()`, see `try_set_a()`.
+    pub fn try_set_b(&mut self, mat: SMatrix<T, NX, NU>) -> Result<&mut Self, StateSpaceError> {
+        check_finite(&mat)?;
+        Ok(self.set_b(mat))
+    }
+
+    /// Fallible version of `set_c()`, see `try_set_a()`.
+    pub fn try_set_c(&mut self, mat: SMatrix<T, NY, NX>) -> Result<&mut Self, StateSpaceError> {
+        check_finite(&mat)?;
+        Ok(self.set_c(mat))
+    }
+
+    /// Fallible version of `set_d()`, see `try_set_a()`.
+    pub fn try_set_d(&mut self, mat: SMatrix<T, NY, NU>) -> Result<&mut Self, StateSpaceError> {
+        check_finite(&mat)?;
+        Ok(self.set_d(mat))
+    }
+}